@@ -0,0 +1,9 @@
+//! Compares results saved with `--save-baseline` or `--format=json`.
+//!
+//! ```sh
+//! cargo run -p divan-compare -- main.json feature.json
+//! ```
+
+fn main() {
+    divan::compare::main();
+}
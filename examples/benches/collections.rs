@@ -54,6 +54,7 @@ fn with_capacity<T: util::WithCapacity>(bencher: Bencher, len: usize) {
         BTreeSet<i32>,
     ],
     args = LENS,
+    baseline_type = Vec<i32>,
 )]
 fn from_iter<T: FromIterator<i32>>(bencher: Bencher, len: usize) {
     bencher.counter(len).bench(|| util::collect_nums::<T>(len))
@@ -44,7 +44,7 @@ mod fibonacci {
     const VALUES: &[u64] = &[0, 5, 10, 20, 30, 40];
 
     // O(n)
-    #[divan::bench(args = VALUES)]
+    #[divan::bench(args = VALUES, baseline = recursive)]
     fn iterative(n: u64) -> u64 {
         let mut previous = 1;
         let mut current = 1;
@@ -97,6 +97,7 @@ mod fibonacci {
     #[divan::bench(
         types = [BTreeMap<u64, u64>, HashMap<u64, u64>],
         args = VALUES,
+        baseline = recursive,
     )]
     fn recursive_memoized<M: Map>(n: u64) -> u64 {
         fn fibonacci<M: Map>(n: u64, cache: &mut M) -> u64 {
@@ -33,6 +33,12 @@ pub(crate) struct AttrOptions {
     /// Options for generic functions.
     pub generic: GenericOptions,
 
+    /// Path to the benchmark function to compare against.
+    pub baseline: Option<syn::Path>,
+
+    /// Generic type whose benchmark instances to compare against.
+    pub baseline_type: Option<Type>,
+
     /// The `BenchOptions.counters` field and its value, followed by a comma.
     pub counters: proc_macro2::TokenStream,
 
@@ -50,6 +56,8 @@ impl AttrOptions {
         let mut divan_crate = None::<syn::Path>;
         let mut name_expr = None::<Expr>;
         let mut args_expr = None::<Expr>;
+        let mut baseline = None::<syn::Path>;
+        let mut baseline_type = None::<Type>;
         let mut bench_options = Vec::new();
 
         let mut counters = Vec::<(proc_macro2::TokenStream, Option<&str>)>::new();
@@ -127,6 +135,25 @@ impl AttrOptions {
 
                     parse!(args_expr);
                 }
+                "baseline" => {
+                    if !matches!(target_macro, Macro::Bench { .. }) {
+                        return unsupported_error();
+                    }
+
+                    parse!(baseline);
+                }
+                "baseline_type" => {
+                    match target_macro {
+                        Macro::Bench { fn_sig } => {
+                            if fn_sig.generics.type_params().next().is_none() {
+                                error!("generic type required for '{macro_name}' option '{ident_name}'");
+                            }
+                        }
+                        _ => return unsupported_error(),
+                    }
+
+                    parse!(baseline_type);
+                }
                 "counter" => {
                     if counters_ident.is_some() {
                         return repeat_error();
@@ -195,6 +222,24 @@ impl AttrOptions {
             Err(error) => return Err(error.into_compile_error().into()),
         }
 
+        if let Some(baseline_type) = &baseline_type {
+            let error = if generic.types.is_none() {
+                Some(format!("'{macro_name}' option 'baseline_type' requires option 'types'"))
+            } else if baseline.is_some() {
+                Some(format!(
+                    "'{macro_name}' options 'baseline' and 'baseline_type' cannot be used together"
+                ))
+            } else {
+                None
+            };
+
+            if let Some(error) = error {
+                return Err(syn::Error::new(baseline_type.span(), error)
+                    .into_compile_error()
+                    .into());
+            }
+        }
+
         let divan_crate = divan_crate.unwrap_or_else(|| syn::parse_quote!(::divan));
         let private_mod = quote! { #divan_crate::__private };
         let std_crate = quote! { #private_mod::std };
@@ -223,7 +268,17 @@ impl AttrOptions {
             })
             .unwrap_or_default();
 
-        Ok(Self { std_crate, private_mod, name_expr, args_expr, generic, counters, bench_options })
+        Ok(Self {
+            std_crate,
+            private_mod,
+            name_expr,
+            args_expr,
+            generic,
+            baseline,
+            baseline_type,
+            counters,
+            bench_options,
+        })
     }
 
     /// Produces a function expression for creating `BenchOptions`.
@@ -279,12 +334,41 @@ impl AttrOptions {
 
                     // If the option is a `Duration`, use `IntoDuration` to be
                     // polymorphic over `Duration` or `u64`/`f64` seconds.
-                    "min_time" | "max_time" => {
+                    "warmup_time" | "min_time" | "max_time" | "max_sample_time" => {
                         wrapped_value =
                             quote! { #private_mod::IntoDuration::into_duration(#value) };
                         &wrapped_value
                     }
 
+                    "target_precision" => {
+                        wrapped_value = quote! { #private_mod::target_precision(#value) };
+                        &wrapped_value
+                    }
+
+                    // Allow `sampling = linear` as shorthand for
+                    // `sampling = divan::Sampling::Linear`.
+                    "sampling" => {
+                        let variant = match value {
+                            Expr::Path(expr) => expr.path.get_ident().and_then(|ident| match ident
+                                .to_string()
+                                .as_str()
+                            {
+                                "flat" => Some(quote! { Flat }),
+                                "linear" => Some(quote! { Linear }),
+                                _ => None,
+                            }),
+                            _ => None,
+                        };
+
+                        match variant {
+                            Some(variant) => {
+                                wrapped_value = quote! { #private_mod::Sampling::#variant };
+                                &wrapped_value
+                            }
+                            None => value,
+                        }
+                    }
+
                     _ => value,
                 };
 
@@ -592,6 +592,31 @@ fn entry_meta_expr(
 
     let bench_options_fn = options.bench_options_fn(ignore_attr_ident);
 
+    // Reference the entry static generated for the baseline function.
+    let baseline = match &options.baseline {
+        Some(path) => {
+            let mut path = path.clone();
+
+            if let Some(segment) = path.segments.last_mut() {
+                let name = segment.ident.to_string();
+                let name = name.strip_prefix("r#").unwrap_or(&name);
+
+                segment.ident = syn::Ident::new(
+                    &format!("__DIVAN_BENCH_{}", name.to_uppercase()),
+                    segment.ident.span(),
+                );
+            }
+
+            quote! { #private_mod::Some(&#path.meta) }
+        }
+        None => quote! { #private_mod::None },
+    };
+
+    let baseline_type = match &options.baseline_type {
+        Some(ty) => quote! { #private_mod::Some(#private_mod::EntryType::new::<#ty>()) },
+        None => quote! { #private_mod::None },
+    };
+
     quote! {
         #private_mod::EntryMeta {
             raw_name: #raw_name,
@@ -607,6 +632,9 @@ fn entry_meta_expr(
 
             get_bench_options: #bench_options_fn,
             cached_bench_options: #private_mod::OnceLock::new(),
+
+            baseline: #baseline,
+            baseline_type: #baseline_type,
         }
     }
 }
@@ -367,17 +367,27 @@ impl<C> AllocTally<C> {
 
 /// Allocation number categories.
 ///
-/// Note that grow/shrink are first to improve code generation for `realloc`.
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub(crate) enum AllocOp {
+/// This is used by [`Stats::alloc_count`](crate::report::Stats::alloc_count)
+/// and [`Stats::alloc_size`](crate::report::Stats::alloc_size).
+//
+// Note that grow/shrink are first to improve code generation for `realloc`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AllocOp {
+    /// Memory grown by `realloc`.
     Grow,
+
+    /// Memory shrunk by `realloc`.
     Shrink,
+
+    /// Memory allocated by `alloc` or `alloc_zeroed`.
     Alloc,
+
+    /// Memory freed by `dealloc`.
     Dealloc,
 }
 
 impl AllocOp {
-    pub const ALL: [Self; 4] = {
+    pub(crate) const ALL: [Self; 4] = {
         use AllocOp::*;
 
         // Use same order as declared so that it can be indexed as-is.
@@ -385,7 +395,7 @@ impl AllocOp {
     };
 
     #[inline]
-    pub fn realloc(shrink: bool) -> Self {
+    pub(crate) fn realloc(shrink: bool) -> Self {
         // This generates the same code as `std::mem::transmute`.
         if shrink {
             Self::Shrink
@@ -395,7 +405,7 @@ impl AllocOp {
     }
 
     #[inline]
-    pub fn name(self) -> &'static str {
+    pub(crate) fn name(self) -> &'static str {
         match self {
             Self::Grow => "grow",
             Self::Shrink => "shrink",
@@ -405,7 +415,7 @@ impl AllocOp {
     }
 
     #[inline]
-    pub fn prefix(self) -> &'static str {
+    pub(crate) fn prefix(self) -> &'static str {
         match self {
             Self::Grow => "grow:",
             Self::Shrink => "shrink:",
@@ -5,6 +5,7 @@ use std::{
     num::NonZeroUsize,
     sync::Barrier,
     thread,
+    time::Duration,
 };
 
 use crate::{
@@ -18,7 +19,10 @@ use crate::{
         ItemsCount, KnownCounterKind, MaxCountUInt,
     },
     divan::SharedContext,
-    stats::{RawSample, SampleCollection, Stats, StatsSet, ThreadSample, TimeSample},
+    stats::{
+        Bootstrap, ConfidenceInterval, Distribution, Outliers, RawSample, Regression,
+        SampleCollection, Stats, StatsSet, ThreadSample, TimeSample,
+    },
     time::{FineDuration, Timestamp, UntaggedTimestamp},
     util::{self, SyncWrap, Unit},
 };
@@ -34,11 +38,30 @@ use defer::{DeferSlot, DeferStore};
 
 pub use self::{
     args::{BenchArgs, BenchArgsRunner},
-    options::BenchOptions,
+    options::{BenchOptions, Sampling},
 };
 
+pub(crate) use self::options::PrivSampling;
+
 pub(crate) const DEFAULT_SAMPLE_COUNT: u32 = 100;
 
+/// The default wall time budget for a single sample.
+pub(crate) const DEFAULT_MAX_SAMPLE_TIME: Duration = Duration::from_millis(10);
+
+/// The number of resamples for checking whether
+/// [`BenchOptions::target_precision`] is reached, which needs less accuracy
+/// than reported confidence intervals.
+const PRECISION_RESAMPLES: u32 = 1_000;
+
+/// The number of samples collected before first checking whether
+/// [`BenchOptions::target_precision`] is reached.
+const MIN_PRECISION_SAMPLE_COUNT: usize = 10;
+
+/// How many times the sample count can grow past
+/// [`BenchOptions::sample_count`] while the target precision is not reached.
+/// This bounds sampling when `max_time` is not set.
+const MAX_PRECISION_SAMPLE_GROWTH: u32 = 64;
+
 /// Enables contextual benchmarking in [`#[divan::bench]`](attr.bench.html).
 ///
 /// # Examples
@@ -477,6 +500,10 @@ pub(crate) enum BenchMode {
     /// Don't collect samples and run exactly once.
     Test,
 
+    /// Run without recording samples, doubling `sample_size` until
+    /// `warmup_iters` and `warmup_time` are reached.
+    Warmup { sample_size: u32 },
+
     /// Scale `sample_size` to determine the right size for collecting.
     Tune { sample_size: u32 },
 
@@ -504,7 +531,9 @@ impl BenchMode {
     pub fn sample_size(self) -> u32 {
         match self {
             Self::Test => 1,
-            Self::Tune { sample_size, .. } | Self::Collect { sample_size, .. } => sample_size,
+            Self::Warmup { sample_size, .. }
+            | Self::Tune { sample_size, .. }
+            | Self::Collect { sample_size, .. } => sample_size,
         }
     }
 }
@@ -640,11 +669,12 @@ impl<'a> BenchContext<'a> {
         let timer = self.shared_context.timer;
         let timer_kind = timer.kind();
 
-        let mut rem_samples = if current_mode.is_collect() {
-            Some(self.options.sample_count.unwrap_or(DEFAULT_SAMPLE_COUNT))
-        } else {
-            None
-        };
+        // The number of samples to collect, which linear sampling may reduce
+        // to stay within the time of flat sampling.
+        let mut collect_sample_count = self.options.sample_count.unwrap_or(DEFAULT_SAMPLE_COUNT);
+
+        let mut rem_samples =
+            if current_mode.is_collect() { Some(collect_sample_count) } else { None };
 
         // Only measure precision if we need to tune sample size.
         let timer_precision =
@@ -654,8 +684,43 @@ impl<'a> BenchContext<'a> {
             self.samples.time_samples.reserve(self.options.sample_count.unwrap_or(1) as usize);
         }
 
+        // With linear sampling, the size of each collected sample set is the
+        // collecting sample size multiplied by this step, which increments
+        // after each set and wraps around after `collect_sample_count`.
+        let is_linear = self.options.sampling == Some(Sampling::Linear);
+        let mut linear_step: u32 = 1;
+
+        // Checking precision resamples every sample, so it is only checked
+        // each time the sample count doubles to keep this cost low.
+        let target_precision = self.options.target_precision;
+        let precision_bootstrap =
+            Bootstrap { resamples: PRECISION_RESAMPLES, ..self.shared_context.bootstrap };
+        let max_precision_samples = (self.options.sample_count.unwrap_or(DEFAULT_SAMPLE_COUNT)
+            as usize)
+            .saturating_mul(MAX_PRECISION_SAMPLE_GROWTH as usize);
+        let mut next_precision_check = MIN_PRECISION_SAMPLE_COUNT;
+
+        // Warm up before tuning or collecting, which then starts with the
+        // initial mode. Warm-up is not counted towards `min_time` or
+        // `max_time`.
+        let warmup_iters = self.options.warmup_iters.unwrap_or_default();
+        let warmup_picos = self.options.warmup_time().picos;
+        let mut warmup_done_iters: u32 = 0;
+        let warmup_start = Timestamp::start(timer_kind);
+
+        if !is_test && (warmup_iters > 0 || warmup_picos > 0) {
+            current_mode = BenchMode::Warmup { sample_size: 1 };
+        }
+
+        // Automatically chosen sample sizes are limited by the wall time of
+        // each sample set, which includes generating inputs and dropping
+        // inputs and outputs.
+        let max_sample_picos = self.options.max_sample_time().picos;
+        let is_sample_size_fixed = self.options.sample_size.is_some();
+
         let skip_ext_time = self.options.skip_ext_time.unwrap_or_default();
-        let initial_start = if skip_ext_time { None } else { Some(Timestamp::start(timer_kind)) };
+        let mut initial_start =
+            if skip_ext_time { None } else { Some(Timestamp::start(timer_kind)) };
 
         while {
             // Conditions for when sampling is over:
@@ -671,8 +736,13 @@ impl<'a> BenchContext<'a> {
                 elapsed_picos < min_picos
             }
         } {
-            let sample_size = current_mode.sample_size();
-            self.samples.sample_size = sample_size;
+            let sample_step = linear_step;
+            let sample_size = match current_mode {
+                BenchMode::Collect { sample_size } if is_linear => {
+                    sample_size.saturating_mul(linear_step)
+                }
+                mode => mode.sample_size(),
+            };
 
             let barrier = if is_single_thread { None } else { Some(Barrier::new(thread_count)) };
 
@@ -707,6 +777,7 @@ impl<'a> BenchContext<'a> {
             };
 
             // Sample loop:
+            let sample_start = Timestamp::start(timer_kind);
             raw_samples.clear();
             if is_single_thread {
                 let sample = record_sample(&mut defer_store);
@@ -754,36 +825,99 @@ impl<'a> BenchContext<'a> {
                 break;
             }
 
+            if let BenchMode::Warmup { sample_size } = current_mode {
+                warmup_done_iters = warmup_done_iters.saturating_add(sample_size);
+
+                let last_end = raw_samples.iter().map(|s| s.end).max().unwrap();
+                let warmup_elapsed = last_end.duration_since(warmup_start, timer).picos;
+
+                if warmup_done_iters >= warmup_iters && warmup_elapsed >= warmup_picos {
+                    current_mode = self.initial_mode();
+
+                    if !skip_ext_time {
+                        initial_start = Some(Timestamp::start(timer_kind));
+                    }
+                } else {
+                    let rem_iters = warmup_iters.saturating_sub(warmup_done_iters);
+
+                    // Estimate the iterations left to reach `warmup_time`.
+                    let rem_time_iters = if warmup_elapsed < warmup_picos {
+                        let iter_picos = (warmup_elapsed / warmup_done_iters as u128).max(1);
+                        let iters = (warmup_picos - warmup_elapsed) / iter_picos;
+                        iters.clamp(1, u32::MAX as u128) as u32
+                    } else {
+                        0
+                    };
+
+                    // Double the sample size without overshooting the rest of
+                    // warm-up.
+                    let next_size =
+                        sample_size.saturating_mul(2).min(rem_iters.max(rem_time_iters));
+
+                    current_mode = BenchMode::Warmup { sample_size: next_size };
+                }
+
+                continue;
+            }
+
             let slowest_sample = raw_samples.iter().max_by_key(|s| s.duration()).unwrap();
             let slowest_time = slowest_sample.duration();
 
-            // TODO: Make tuning be less influenced by early runs. Currently if
-            // early runs are very quick but later runs are slow, benchmarking
-            // will take a very long time.
-            //
-            // TODO: Make `sample_size` consider time generating inputs and
-            // dropping inputs/outputs. Currently benchmarks like
-            // `Bencher::bench_refs(String::clear)` take a very long time.
+            // Unlike `slowest_time`, this includes time spent generating inputs
+            // and dropping inputs and outputs.
+            let sample_wall_picos =
+                Timestamp::start(timer_kind).duration_since(sample_start, timer).picos;
+
+            // The last tuning sample is kept as the first collected sample,
+            // unless linear sampling needs a different size.
+            let mut discard_sample = false;
+
             if current_mode.is_tune() {
                 // Clear previous smaller samples.
                 self.samples.clear();
                 self.counters.clear_input_counts();
 
-                // If within 100x timer precision, continue tuning.
+                // If within 100x timer precision, continue tuning unless
+                // doubling would exceed the per-sample time budget.
                 let precision_multiple = slowest_time.picos / timer_precision.picos;
-                if precision_multiple <= 100 {
+                let can_double = sample_wall_picos.saturating_mul(2) <= max_sample_picos;
+                if precision_multiple <= 100 && can_double {
                     current_mode = BenchMode::Tune { sample_size: sample_size * 2 };
                 } else {
+                    let sample_count = self.options.sample_count.unwrap_or(DEFAULT_SAMPLE_COUNT);
+
+                    let (sample_size, sample_count) = if is_linear {
+                        linear_plan(sample_size, sample_count)
+                    } else {
+                        (sample_size, sample_count)
+                    };
+
                     current_mode = BenchMode::Collect { sample_size };
-                    rem_samples = Some(self.options.sample_count.unwrap_or(DEFAULT_SAMPLE_COUNT));
+                    collect_sample_count = sample_count;
+                    rem_samples = Some(sample_count);
+                    discard_sample = is_linear;
                 }
+            } else if is_linear {
+                linear_step = linear_step % collect_sample_count.max(1) + 1;
             }
 
+            // Samples that exceed the per-sample time budget, such as when
+            // later runs are much slower than those used to tune, restart
+            // collection with a smaller sample size. This uses the size of the
+            // sample just taken, which linear sampling scales by its step.
+            let should_shrink = current_mode.is_collect()
+                && !is_sample_size_fixed
+                && sample_size > 1
+                && sample_wall_picos > max_sample_picos;
+
             // Account the sample duration for the per-sample benchmarking
-            // overhead.
+            // overhead. Linear sampling instead cancels overhead by regression.
             let sub_sample_overhead = {
-                let overhead =
-                    self.shared_context.bench_overhead.picos.saturating_mul(sample_size as u128);
+                let overhead = if is_linear {
+                    0
+                } else {
+                    self.shared_context.bench_overhead.picos.saturating_mul(sample_size as u128)
+                };
 
                 move |d: FineDuration| {
                     FineDuration {
@@ -808,9 +942,10 @@ impl<'a> BenchContext<'a> {
             for raw_sample in &raw_samples {
                 let sample_index = self.samples.time_samples.len();
 
-                self.samples
-                    .time_samples
-                    .push(TimeSample { duration: sub_sample_overhead(raw_sample.duration()) });
+                self.samples.time_samples.push(TimeSample {
+                    duration: sub_sample_overhead(raw_sample.duration()),
+                    sample_size,
+                });
 
                 if !raw_sample.alloc_tallies.is_empty() {
                     self.samples
@@ -847,6 +982,60 @@ impl<'a> BenchContext<'a> {
                 let progress_picos = slowest_time.picos.max(1_000);
                 elapsed_picos = elapsed_picos.saturating_add(progress_picos);
             }
+
+            if discard_sample {
+                self.samples.clear();
+                self.counters.clear_input_counts();
+                rem_samples = Some(collect_sample_count);
+                continue;
+            }
+
+            // Discard samples collected so far rather than mixing sample sizes,
+            // which would skew stats and break linear sampling's progression.
+            if should_shrink {
+                let base_size = current_mode.sample_size();
+                if base_size > 1 {
+                    current_mode = BenchMode::Collect { sample_size: base_size / 2 };
+                } else {
+                    // Linear sampling of single iterations can only shrink by
+                    // taking fewer steps, up to before the one that was slow.
+                    collect_sample_count = (sample_step - 1).max(1);
+                }
+
+                self.samples.clear();
+                self.counters.clear_input_counts();
+                rem_samples = Some(collect_sample_count);
+                linear_step = 1;
+                next_precision_check = MIN_PRECISION_SAMPLE_COUNT;
+                continue;
+            }
+
+            // Stop early once the median is precise enough, or continue if it
+            // is not yet precise enough when sampling would otherwise stop.
+            if let Some(target_precision) = target_precision {
+                let sample_count = self.samples.time_samples.len();
+                let is_checkpoint = sample_count >= next_precision_check || rem_samples == Some(0);
+
+                if current_mode.is_collect()
+                    && is_checkpoint
+                    && elapsed_picos >= min_picos
+                    && elapsed_picos < max_picos
+                {
+                    next_precision_check = sample_count.saturating_mul(2);
+
+                    match self.samples.median_margin(&precision_bootstrap) {
+                        Some(margin) if margin <= target_precision => {
+                            rem_samples = Some(0);
+                        }
+                        Some(_) if rem_samples == Some(0) => {
+                            let extra = sample_count
+                                .min(max_precision_samples.saturating_sub(sample_count));
+                            rem_samples = Some(extra as u32);
+                        }
+                        _ => {}
+                    }
+                }
+            }
         }
     }
 
@@ -1111,18 +1300,37 @@ impl<'a> BenchContext<'a> {
         let alloc_samples = &self.samples.alloc_tallies;
 
         let sample_count = time_samples.len();
-        let sample_size = self.samples.sample_size;
 
         let total_count = self.samples.iter_count();
 
-        let total_duration = self.samples.total_duration();
-        let mean_duration = FineDuration {
-            picos: total_duration.picos.checked_div(total_count as u128).unwrap_or_default(),
+        // Samples sorted by duration per iteration.
+        let sorted_samples = self.samples.sorted_samples();
+
+        let sorted_nanos: Vec<f64> =
+            sorted_samples.iter().map(|s| s.iter_duration().as_nanos_f64()).collect();
+
+        let outliers = Outliers::from_sorted(&sorted_nanos);
+
+        // Samples used for the median and mean, which exclude outliers if
+        // requested.
+        let inliers = if self.shared_context.exclude_outliers {
+            outliers.inliers(sample_count)
+        } else {
+            0..sample_count
         };
+        let inlier_samples = &sorted_samples[inliers.clone()];
+        let median_samples = util::slice_middle(inlier_samples);
 
-        // Samples sorted by duration.
-        let sorted_samples = self.samples.sorted_samples();
-        let median_samples = util::slice_middle(&sorted_samples);
+        let mean_duration = if self.shared_context.exclude_outliers {
+            let inlier_duration: u128 = inlier_samples.iter().map(|s| s.duration.picos).sum();
+            let inlier_count: u128 = inlier_samples.iter().map(|s| s.sample_size as u128).sum();
+            FineDuration { picos: inlier_duration.checked_div(inlier_count).unwrap_or_default() }
+        } else {
+            let total_duration = self.samples.total_duration();
+            FineDuration {
+                picos: total_duration.picos.checked_div(total_count as u128).unwrap_or_default(),
+            }
+        };
 
         let index_of_sample = |sample: &TimeSample| -> usize {
             util::slice_ptr_index(&self.samples.time_samples, sample)
@@ -1141,16 +1349,32 @@ impl<'a> BenchContext<'a> {
                 counts.get(index).copied()
             };
 
-        let min_duration =
-            sorted_samples.first().map(|s| s.duration / sample_size).unwrap_or_default();
-        let max_duration =
-            sorted_samples.last().map(|s| s.duration / sample_size).unwrap_or_default();
+        let min_duration = sorted_samples.first().map(|s| s.iter_duration()).unwrap_or_default();
+        let max_duration = sorted_samples.last().map(|s| s.iter_duration()).unwrap_or_default();
+
+        let time_distribution = Distribution::from_sorted(&sorted_nanos)
+            .map(|&nanos| FineDuration::from_nanos_f64(nanos));
+
+        // Resampling does not depend on sample order.
+        let time_cis = self.shared_context.bootstrap.estimate(&sorted_nanos[inliers]);
+        let time_ci =
+            |ci: &ConfidenceInterval<f64>| ci.map(|&nanos| FineDuration::from_nanos_f64(nanos));
+
+        // Least-squares fit of sample durations against sample sizes, whose
+        // slope cancels constant per-sample overhead. This requires samples of
+        // varying sizes, such as with linear sampling.
+        let regression_points: Vec<(f64, f64)> = time_samples
+            .iter()
+            .map(|s| (f64::from(s.sample_size), s.duration.as_nanos_f64()))
+            .collect();
+        let regression = Regression::fit(&regression_points);
 
         let median_duration = if median_samples.is_empty() {
             FineDuration::default()
         } else {
             let sum: u128 = median_samples.iter().map(|s| s.duration.picos).sum();
-            FineDuration { picos: sum / median_samples.len() as u128 } / sample_size
+            let count: u128 = median_samples.iter().map(|s| s.sample_size as u128).sum();
+            FineDuration { picos: sum / count }
         };
 
         let counts = KnownCounterKind::ALL.map(|counter_kind| {
@@ -1202,11 +1426,17 @@ impl<'a> BenchContext<'a> {
                 median: median_duration,
                 mean: mean_duration,
             },
+            time_distribution,
+            outliers,
+            median_ci: time_cis.as_ref().map(|cis| time_ci(&cis.median)),
+            mean_ci: time_cis.as_ref().map(|cis| time_ci(&cis.mean)),
             alloc_tallies: AllocOpMap {
                 values: AllocOp::ALL
                     .map(|op| StatsSet {
                         fastest: {
-                            let fastest = sample_alloc_tally(sorted_samples.first().copied(), op);
+                            let sample = sorted_samples.first().copied();
+                            let fastest = sample_alloc_tally(sample, op);
+                            let sample_size = sample.map_or(1, |s| s.sample_size);
 
                             AllocTally {
                                 count: fastest.count as f64 / f64::from(sample_size),
@@ -1214,7 +1444,9 @@ impl<'a> BenchContext<'a> {
                             }
                         },
                         slowest: {
-                            let slowest = sample_alloc_tally(sorted_samples.last().copied(), op);
+                            let sample = sorted_samples.last().copied();
+                            let slowest = sample_alloc_tally(sample, op);
+                            let sample_size = sample.map_or(1, |s| s.sample_size);
 
                             AllocTally {
                                 count: slowest.count as f64 / f64::from(sample_size),
@@ -1234,9 +1466,16 @@ impl<'a> BenchContext<'a> {
                             let total_count = (a.count + b.count) / median_count;
                             let total_size = (a.size + b.size) / median_count;
 
+                            // Average size of the median samples.
+                            let sample_size = median_samples
+                                .iter()
+                                .map(|s| f64::from(s.sample_size))
+                                .sum::<f64>()
+                                / median_samples.len().max(1) as f64;
+
                             AllocTally {
-                                count: total_count as f64 / f64::from(sample_size),
-                                size: total_size as f64 / f64::from(sample_size),
+                                count: total_count as f64 / sample_size,
+                                size: total_size as f64 / sample_size,
                             }
                         },
                         mean: {
@@ -1250,13 +1489,39 @@ impl<'a> BenchContext<'a> {
                     .map(StatsSet::transpose),
             },
             counts,
+            samples: time_samples.iter().map(TimeSample::iter_duration).collect(),
+            regression,
         }
     }
 }
 
+/// Returns the base sample size and number of sample sets for linear sampling,
+/// given the sample size tuned for flat sampling.
+///
+/// Linear sample sizes average `(n + 1) / 2` times the base, so the base is
+/// scaled down to keep the same total iterations as `sample_count` flat
+/// samples. If that would be below 1, fewer sets of single iterations are
+/// taken instead.
+fn linear_plan(sample_size: u32, sample_count: u32) -> (u32, u32) {
+    let total = sample_size as u64 * sample_count as u64;
+
+    let base = sample_size as u64 * 2 / (sample_count as u64 + 1);
+    if base >= 1 {
+        return (base as u32, sample_count);
+    }
+
+    // The most steps where `1 + 2 + … + steps <= total`.
+    let mut steps = ((((8 * total + 1) as f64).sqrt() - 1.0) / 2.0) as u64;
+    while steps * (steps + 1) / 2 > total {
+        steps -= 1;
+    }
+
+    (1, steps.clamp(1, sample_count as u64) as u32)
+}
+
 impl<T> StatsSet<AllocTally<T>> {
     #[inline]
-    pub fn transpose(self) -> AllocTally<StatsSet<T>> {
+    pub(crate) fn transpose(self) -> AllocTally<StatsSet<T>> {
         AllocTally {
             count: StatsSet {
                 fastest: self.fastest.count,
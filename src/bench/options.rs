@@ -1,6 +1,6 @@
 use std::{borrow::Cow, time::Duration};
 
-use crate::{counter::CounterSet, time::FineDuration};
+use crate::{bench::DEFAULT_MAX_SAMPLE_TIME, counter::CounterSet, time::FineDuration};
 
 /// Benchmarking options set directly by the user in `#[divan::bench]` and
 /// `#[divan::bench_group]`.
@@ -13,8 +13,13 @@ pub struct BenchOptions<'a> {
     pub sample_count: Option<u32>,
 
     /// The number of iterations inside a single sample.
+    ///
+    /// With [`Sampling::Linear`], this is the size of the first sample.
     pub sample_size: Option<u32>,
 
+    /// How sample sizes are chosen.
+    pub sampling: Option<Sampling>,
+
     /// The number of threads to benchmark the sample. This is 1 by default.
     ///
     /// If set to 0, this will use [`std::thread::available_parallelism`].
@@ -27,17 +32,43 @@ pub struct BenchOptions<'a> {
     /// function.
     pub counters: CounterSet,
 
+    /// The minimum time spent running a function before tuning or collecting
+    /// samples, which is not recorded.
+    pub warmup_time: Option<Duration>,
+
+    /// The minimum number of iterations run before tuning or collecting
+    /// samples, which are not recorded.
+    pub warmup_iters: Option<u32>,
+
     /// The time floor for benchmarking a function.
     pub min_time: Option<Duration>,
 
     /// The time ceiling for benchmarking a function.
     pub max_time: Option<Duration>,
 
+    /// The wall time budget for a single sample, including time external to
+    /// benchmarked functions, which limits automatically chosen sample sizes.
+    pub max_sample_time: Option<Duration>,
+
     /// When accounting for `min_time` or `max_time`, skip time external to
     /// benchmarked functions, such as time spent generating inputs and running
     /// [`Drop`].
     pub skip_ext_time: Option<bool>,
 
+    /// The relative margin of the confidence interval around the median time,
+    /// such as `0.01` for ±1%, below which sampling stops.
+    ///
+    /// Sampling stops before `sample_count` once this is reached, or continues
+    /// past it until this is reached. It does not stop before `min_time` or
+    /// continue past `max_time` or 64 times `sample_count`.
+    pub target_precision: Option<f64>,
+
+    /// The fractional increase in median time beyond which a statistically
+    /// significant slowdown from the baseline is considered a regression.
+    ///
+    /// This overrides the `--fail-on-regression` CLI argument.
+    pub regression_threshold: Option<f64>,
+
     /// Whether the benchmark should be ignored.
     ///
     /// This may be set within the attribute or with a separate
@@ -56,10 +87,16 @@ impl<'a> BenchOptions<'a> {
             // `Copy` values:
             sample_count: self.sample_count.or(other.sample_count),
             sample_size: self.sample_size.or(other.sample_size),
+            sampling: self.sampling.or(other.sampling),
             threads: self.threads.as_deref().or(other.threads.as_deref()).map(Cow::Borrowed),
+            warmup_time: self.warmup_time.or(other.warmup_time),
+            warmup_iters: self.warmup_iters.or(other.warmup_iters),
             min_time: self.min_time.or(other.min_time),
             max_time: self.max_time.or(other.max_time),
+            max_sample_time: self.max_sample_time.or(other.max_sample_time),
             skip_ext_time: self.skip_ext_time.or(other.skip_ext_time),
+            target_precision: self.target_precision.or(other.target_precision),
+            regression_threshold: self.regression_threshold.or(other.regression_threshold),
             ignore: self.ignore.or(other.ignore),
 
             // `Clone` values:
@@ -73,6 +110,11 @@ impl<'a> BenchOptions<'a> {
         self.sample_count != Some(0) && self.sample_size != Some(0)
     }
 
+    #[inline]
+    pub(crate) fn warmup_time(&self) -> FineDuration {
+        self.warmup_time.map(FineDuration::from).unwrap_or_default()
+    }
+
     #[inline]
     pub(crate) fn min_time(&self) -> FineDuration {
         self.min_time.map(FineDuration::from).unwrap_or_default()
@@ -82,4 +124,54 @@ impl<'a> BenchOptions<'a> {
     pub(crate) fn max_time(&self) -> FineDuration {
         self.max_time.map(FineDuration::from).unwrap_or(FineDuration::MAX)
     }
+
+    #[inline]
+    pub(crate) fn max_sample_time(&self) -> FineDuration {
+        self.max_sample_time.unwrap_or(DEFAULT_MAX_SAMPLE_TIME).into()
+    }
+}
+
+/// How the number of iterations inside each sample is chosen.
+///
+/// See [`#[divan::bench(sampling = ...)]`](macro@crate::bench#sampling) for
+/// more info.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Sampling {
+    /// Every sample has the same number of iterations.
+    ///
+    /// The time per iteration of each sample has the timer's per-sample
+    /// overhead subtracted.
+    #[default]
+    Flat,
+
+    /// Sample sizes grow linearly: `k`, `2k`, `3k`, and so on.
+    ///
+    /// Automatically chosen sample sizes are scaled to run about as many
+    /// iterations as [`Flat`](Self::Flat), with fewer samples if needed.
+    ///
+    /// The time per iteration is estimated as the slope of a least-squares
+    /// fit of sample times over sample sizes, which cancels constant
+    /// per-sample overhead. Other time stats, such as the median and mean,
+    /// include this overhead because it is not subtracted from samples.
+    Linear,
+}
+
+/// Private `Sampling` that prevents leaking trait implementations we don't
+/// want to publicly commit to.
+#[derive(Clone, Copy)]
+pub(crate) struct PrivSampling(pub Sampling);
+
+impl clap::ValueEnum for PrivSampling {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self(Sampling::Flat), Self(Sampling::Linear)]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let name = match self.0 {
+            Sampling::Flat => "flat",
+            Sampling::Linear => "linear",
+        };
+        Some(clap::builder::PossibleValue::new(name))
+    }
 }
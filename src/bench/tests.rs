@@ -41,8 +41,13 @@ fn test_bencher(test: &mut dyn FnMut(Bencher)) {
 
     for timer in Timer::available() {
         for action in [Action::Bench, Action::Test] {
-            let shared_context =
-                SharedContext { action, timer, bench_overhead: FineDuration::default() };
+            let shared_context = SharedContext {
+                action,
+                timer,
+                bench_overhead: FineDuration::default(),
+                bootstrap: Default::default(),
+                exclude_outliers: false,
+            };
 
             for &thread_count in THREAD_COUNTS {
                 let mut bench_context = BenchContext::new(
@@ -550,3 +555,17 @@ mod zst_input {
         assert_eq!(ZST_COUNT.load(SeqCst), 0);
     }
 }
+
+#[test]
+fn linear_plan() {
+    // The base size is scaled so that 1 + 2 + … + 10 sets of it take about as
+    // many iterations as 10 flat samples.
+    assert_eq!(super::linear_plan(110, 10), (20, 10));
+    assert_eq!(super::linear_plan(11, 10), (2, 10));
+
+    // Sets of single iterations are capped at the flat total.
+    assert_eq!(super::linear_plan(1, 100), (1, 13));
+    assert_eq!(super::linear_plan(5, 10), (1, 9));
+    assert_eq!(super::linear_plan(1, 1), (1, 1));
+    assert_eq!(super::linear_plan(1, 2), (1, 1));
+}
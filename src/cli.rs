@@ -1,7 +1,7 @@
 use clap::{builder::PossibleValue, value_parser, Arg, ArgAction, ColorChoice, Command, ValueEnum};
 
 use crate::{
-    config::{ParsedSeconds, SortingAttr},
+    config::{OutlierMode, ParsedBaselineName, ParsedFraction, ParsedSeconds, SortingAttr},
     counter::MaxCountUInt,
     time::TimerKind,
 };
@@ -20,14 +20,31 @@ pub(crate) fn command() -> Command {
     }
 
     // Custom arguments not supported by libtest:
+    // - append-history
+    // - baseline
+    // - bootstrap-resamples
     // - bytes-format
+    // - columns
+    // - confidence-level
+    // - fail-on-regression
+    // - format (libtest supports pretty|terse|json|junit)
+    // - histogram
+    // - history-label
+    // - html
+    // - max-sample-time
+    // - outliers
+    // - relative
     // - sample-count
     // - sample-size
-    // - timer
+    // - sampling
+    // - save-baseline
+    // - show-trend
     // - sort
     // - sortr
-
-    // TODO: `--format <pretty|terse>`
+    // - target-precision
+    // - timer
+    // - warmup-iters
+    // - warmup-time
 
     Command::new("divan")
         .arg(
@@ -48,6 +65,106 @@ pub(crate) fn command() -> Command {
                 .help("Controls when to use colors")
                 .value_parser(value_parser!(ColorChoice))
         )
+        .arg(
+            option("format")
+                .env("DIVAN_FORMAT")
+                .value_name("pretty|terse|json|json-lines|csv|markdown")
+                .help("Set the format in which results are output")
+                .value_parser(value_parser!(crate::report::PrivOutputFormat)),
+        )
+        .arg(
+            option("columns")
+                .env("DIVAN_COLUMNS")
+                .value_name("COLUMNS")
+                .value_delimiter(',')
+                .action(ArgAction::Append)
+                .help("Set the columns of output, such as 'median,p99,mean,throughput,alloc'")
+                .value_parser(value_parser!(crate::report::PrivColumn)),
+        )
+        .arg(
+            option("html")
+                .env("DIVAN_HTML")
+                .value_name("PATH")
+                .help("Write an HTML report to PATH, or to 'target/divan/report.html' if omitted")
+                .value_parser(value_parser!(std::path::PathBuf))
+                .num_args(0..=1)
+                .require_equals(true),
+        )
+        .arg(
+            option("baseline")
+                .env("DIVAN_BASELINE")
+                .value_name("NAME")
+                .help("Compare results against a baseline saved with '--save-baseline'")
+                .value_parser(value_parser!(ParsedBaselineName)),
+        )
+        .arg(
+            option("fail-on-regression")
+                .env("DIVAN_FAIL_ON_REGRESSION")
+                .value_name("THRESHOLD")
+                .help("Exit with an error if the median time is slower than '--baseline' by THRESHOLD, such as '5%'")
+                .value_parser(value_parser!(ParsedFraction)),
+        )
+        .arg(
+            option("save-baseline")
+                .env("DIVAN_SAVE_BASELINE")
+                .value_name("NAME")
+                .help("Save results as a baseline in 'target/divan/baselines/NAME.json'")
+                .value_parser(value_parser!(ParsedBaselineName)),
+        )
+        .arg(
+            flag("relative")
+                .env("DIVAN_RELATIVE")
+                .help("Show each benchmark's median time as a multiple of its fastest sibling"),
+        )
+        .arg(
+            flag("histogram")
+                .env("DIVAN_HISTOGRAM")
+                .help("Draw a histogram of sample times below each benchmark"),
+        )
+        .arg(
+            option("append-history")
+                .env("DIVAN_APPEND_HISTORY")
+                .value_name("PATH")
+                .help("Append results as a line to the JSON-lines history file at PATH")
+                .value_parser(value_parser!(std::path::PathBuf)),
+        )
+        .arg(
+            option("history-label")
+                .env("DIVAN_HISTORY_LABEL")
+                .value_name("LABEL")
+                .help("Record LABEL with the run appended by '--append-history'"),
+        )
+        .arg(
+            option("show-trend")
+                .env("DIVAN_SHOW_TREND")
+                .value_name("N")
+                .help("Show a sparkline of median times across the last N runs in '--append-history', or 10 if omitted")
+                .value_parser(value_parser!(usize))
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("10"),
+        )
+        .arg(
+            option("confidence-level")
+                .env("DIVAN_CONFIDENCE_LEVEL")
+                .value_name("LEVEL")
+                .help("Set the confidence level of intervals around median and mean times, such as '99%'")
+                .value_parser(value_parser!(ParsedFraction)),
+        )
+        .arg(
+            option("bootstrap-resamples")
+                .env("DIVAN_BOOTSTRAP_RESAMPLES")
+                .value_name("N")
+                .help("Set the number of resamples for estimating confidence intervals, or 0 to disable them")
+                .value_parser(value_parser!(u32)),
+        )
+        .arg(
+            option("outliers")
+                .env("DIVAN_OUTLIERS")
+                .value_name("include|exclude")
+                .help("Set whether outlier samples are included in median and mean times")
+                .value_parser(value_parser!(OutlierMode)),
+        )
         .arg(
             option("skip")
                 .value_name("FILTER")
@@ -97,6 +214,13 @@ pub(crate) fn command() -> Command {
                 .help("Set the number of iterations inside a single sample")
                 .value_parser(value_parser!(u32)),
         )
+        .arg(
+            option("sampling")
+                .env("DIVAN_SAMPLING")
+                .value_name("flat|linear")
+                .help("Set whether sample sizes are the same or grow linearly")
+                .value_parser(value_parser!(crate::bench::PrivSampling)),
+        )
         .arg(
             option("threads")
                 .env("DIVAN_THREADS")
@@ -106,6 +230,20 @@ pub(crate) fn command() -> Command {
                 .help("Run across multiple threads to measure contention on atomics and locks")
                 .value_parser(value_parser!(usize)),
         )
+        .arg(
+            option("warmup-time")
+                .env("DIVAN_WARMUP_TIME")
+                .value_name("SECS")
+                .help("Set the minimum seconds spent running a function before measuring it")
+                .value_parser(value_parser!(ParsedSeconds)),
+        )
+        .arg(
+            option("warmup-iters")
+                .env("DIVAN_WARMUP_ITERS")
+                .value_name("N")
+                .help("Set the minimum number of iterations run before measuring a function")
+                .value_parser(value_parser!(u32)),
+        )
         .arg(
             option("min-time")
                 .env("DIVAN_MIN_TIME")
@@ -120,6 +258,13 @@ pub(crate) fn command() -> Command {
                 .help("Set the maximum seconds spent benchmarking a single function, with priority over '--min-time'")
                 .value_parser(value_parser!(ParsedSeconds)),
         )
+        .arg(
+            option("max-sample-time")
+                .env("DIVAN_MAX_SAMPLE_TIME")
+                .value_name("SECS")
+                .help("Set the maximum seconds spent on a single sample, including time external to benchmarked functions, when choosing sample size")
+                .value_parser(value_parser!(ParsedSeconds)),
+        )
         .arg(
             option("skip-ext-time")
                 .env("DIVAN_SKIP_EXT_TIME")
@@ -128,6 +273,13 @@ pub(crate) fn command() -> Command {
                 .value_parser(value_parser!(bool))
                 .num_args(0..=1),
         )
+        .arg(
+            option("target-precision")
+                .env("DIVAN_TARGET_PRECISION")
+                .value_name("MARGIN")
+                .help("Keep sampling until the confidence interval around the median time is within MARGIN, such as '1%', or until '--max-time'")
+                .value_parser(value_parser!(ParsedFraction)),
+        )
         .arg(
             option("items-count")
                 .env("DIVAN_ITEMS_COUNT")
@@ -174,6 +326,20 @@ impl ValueEnum for TimerKind {
     }
 }
 
+impl ValueEnum for OutlierMode {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Include, Self::Exclude]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self {
+            Self::Include => "include",
+            Self::Exclude => "exclude",
+        };
+        Some(PossibleValue::new(name))
+    }
+}
+
 impl ValueEnum for SortingAttr {
     fn value_variants<'a>() -> &'a [Self] {
         &[Self::Kind, Self::Name, Self::Location]
@@ -0,0 +1,343 @@
+//! Comparison of saved benchmark results.
+//!
+//! This compares results from previous runs without rerunning any benchmarks,
+//! such as runs on different branches or machines. Results can be saved with
+//! `--save-baseline` or output with `--format=json`.
+//!
+//! # Examples
+//!
+//! A binary that compares the files passed as arguments:
+//!
+//! ```no_run
+//! fn main() {
+//!     divan::compare::main();
+//! }
+//! ```
+//!
+//! When run with `main.json feature.json`, this prints the results of
+//! `feature.json` as a tree, with a column showing the change from `main.json`
+//! for each benchmark:
+//!
+//! ```txt
+//! math              fastest  │ slowest  │ median   │ mean     │ samples │ iters   │ main
+//! ╰─ fibonacci               │          │          │          │         │         │
+//!    ├─ iterative   2.124 ns │ 2.208 ns │ 2.135 ns │ 2.141 ns │ 100     │ 1600    │ -4.21% median, -4.05% mean (faster)
+//!    ╰─ recursive   2.101 µs │ 2.287 µs │ 2.117 µs │ 2.133 µs │ 100     │ 100     │ +0.31% median, +0.48% mean (no change)
+//!
+//! Geometric mean of median time changes:
+//!   main: -2.00% across 2 benchmarks (1 faster, 0 slower, 1 no change)
+//! ```
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use clap::{value_parser, Arg, Command};
+
+use crate::{
+    counter::BytesFormat,
+    report::{self, Baseline, Node, NodeKind, Reporter},
+    stats::{Comparison, RunTimes, Stats, Verdict},
+    tree_painter::{ColumnSet, TreeExtras, TreePainter},
+};
+
+/// Compares the result files passed as command-line arguments with
+/// [`compare_files`], exiting with an error if any cannot be loaded.
+pub fn main() {
+    let matches = Command::new("divan-compare")
+        .about("Compare saved Divan benchmark results")
+        .arg(
+            Arg::new("files")
+                .value_name("FILE")
+                .help("Results saved with '--save-baseline' or '--format=json', from oldest to newest")
+                .required(true)
+                .num_args(2..)
+                .value_parser(value_parser!(PathBuf)),
+        )
+        .get_matches();
+
+    let paths: Vec<&PathBuf> = matches.get_many("files").unwrap_or_default().collect();
+
+    if let Err(error) = compare_files(&paths) {
+        eprintln!("error: {error}");
+        std::process::exit(1);
+    }
+}
+
+/// Prints the results of the last file in `paths` as a tree, with a column for
+/// the change from each of the other files.
+///
+/// Each column shows the change in median and mean time, and whether the change
+/// is statistically significant. This is followed by the geometric mean of
+/// median time changes across benchmarks present in both files.
+///
+/// Files can be baselines saved with `--save-baseline` or output from
+/// `--format=json`, and are named after their file stem. Significance is only
+/// shown for baselines, since `--format=json` does not include samples.
+pub fn compare_files<P: AsRef<Path>>(paths: &[P]) -> io::Result<()> {
+    if paths.len() < 2 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "at least two result files are required",
+        ));
+    }
+
+    let results = paths
+        .iter()
+        .map(|path| {
+            let path = path.as_ref();
+            Baseline::load_file(path).map_err(|error| {
+                io::Error::new(error.kind(), format!("{}: {error}", path.display()))
+            })
+        })
+        .collect::<io::Result<Vec<Baseline>>>()?;
+
+    let (new, old) = results.split_last().unwrap();
+
+    let mut tree: Vec<PathTree> = Vec::new();
+    for (segments, stats) in new.iter_segments() {
+        PathTree::insert(&mut tree, segments, stats);
+    }
+
+    // Columns are as wide as their widest value, since all stats are known.
+    let columns = ColumnSet::default();
+    let column_widths = columns
+        .columns
+        .iter()
+        .map(|&column| {
+            new.iter()
+                .map(|(_, stats)| column.get_width(stats, BytesFormat::default()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut painter = TreePainter::new(
+        PathTree::max_name_span(&tree, 0),
+        columns,
+        column_widths,
+        BytesFormat::default(),
+        TreeExtras { baselines: old.iter().collect(), ..TreeExtras::default() },
+    );
+
+    painter.start();
+    PathTree::report(&tree, "", &mut painter);
+    painter.finish();
+
+    println!("Geometric mean of median time changes:");
+    for old in old {
+        println!("  {}: {}", old.name, geometric_mean_summary(old, new));
+    }
+
+    Ok(())
+}
+
+/// Summarizes the median time changes from `old` to `new` across benchmarks
+/// present in both.
+fn geometric_mean_summary(old: &Baseline, new: &Baseline) -> String {
+    let mut count = 0;
+    let mut log_sum = 0.0;
+    let mut verdict_counts = [0usize; 3];
+
+    for (path, stats) in new.iter() {
+        let Some(old_times) = old.get(path) else {
+            continue;
+        };
+
+        let time = stats.time_nanos();
+        let samples: Vec<f64> = stats.sample_nanos().collect();
+        let new_times = RunTimes { median: time.median, mean: time.mean, samples: &samples };
+
+        // Ratios of zero cannot be used in a geometric mean.
+        if old_times.median <= 0.0 || new_times.median <= 0.0 {
+            continue;
+        }
+
+        count += 1;
+        log_sum += (new_times.median / old_times.median).ln();
+
+        // Significance is unknown without samples.
+        if !old_times.samples.is_empty() && !new_times.samples.is_empty() {
+            let verdict_index = match Comparison::new(&old_times, &new_times).verdict {
+                Verdict::Faster => 0,
+                Verdict::Slower => 1,
+                Verdict::NoChange => 2,
+            };
+            verdict_counts[verdict_index] += 1;
+        }
+    }
+
+    if count == 0 {
+        return "no common benchmarks".to_owned();
+    }
+
+    let change = (log_sum / count as f64).exp() - 1.0;
+    let mut summary = format!(
+        "{:+.2}% across {count} benchmark{}",
+        change * 100.0,
+        if count == 1 { "" } else { "s" },
+    );
+
+    if verdict_counts.iter().any(|&count| count > 0) {
+        let [faster, slower, no_change] = verdict_counts;
+        summary = format!("{summary} ({faster} faster, {slower} slower, {no_change} no change)");
+    }
+
+    summary
+}
+
+/// Tree of benchmarks built from their full paths.
+struct PathTree<'a> {
+    name: &'a str,
+    stats: Option<&'a Stats>,
+    children: Vec<PathTree<'a>>,
+}
+
+impl<'a> PathTree<'a> {
+    /// Inserts the benchmark with path `segments` into `tree`, creating
+    /// parents as needed.
+    fn insert(tree: &mut Vec<Self>, segments: &'a [String], stats: &'a Stats) {
+        let Some((name, rest)) = segments.split_first() else {
+            return;
+        };
+        let name = name.as_str();
+
+        let index = match tree.iter().position(|node| node.name == name) {
+            Some(index) => index,
+            None => {
+                tree.push(Self { name, stats: None, children: Vec::new() });
+                tree.len() - 1
+            }
+        };
+
+        let node = &mut tree[index];
+        if rest.is_empty() {
+            node.stats = Some(stats);
+        } else {
+            Self::insert(&mut node.children, rest, stats);
+        }
+    }
+
+    /// Returns the maximum number of characters taken by names and their
+    /// prefixes, matching `EntryTree::max_name_span`.
+    fn max_name_span(tree: &[Self], depth: usize) -> usize {
+        tree.iter()
+            .map(|node| {
+                let prefix_len = depth * 3;
+                let name_len = node.name.chars().count();
+                let child_max = Self::max_name_span(&node.children, depth + 1);
+
+                (prefix_len + name_len).max(child_max)
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn report(tree: &[Self], parent_path: &str, reporter: &mut dyn Reporter) {
+        for (i, tree_node) in tree.iter().enumerate() {
+            let path = report::join_path(parent_path, tree_node.name);
+
+            let node = Node {
+                name: tree_node.name,
+                path: &path,
+                kind: if tree_node.children.is_empty() { NodeKind::Bench } else { NodeKind::Group },
+                is_last: i == tree.len() - 1,
+                baseline: None,
+                thread_count: None,
+            };
+
+            if tree_node.children.is_empty() {
+                reporter.start_leaf(&node);
+                match tree_node.stats {
+                    Some(stats) => reporter.finish_leaf(stats),
+                    None => reporter.finish_empty_leaf(),
+                }
+            } else {
+                reporter.start_parent(&node);
+                Self::report(&tree_node.children, &path, reporter);
+                reporter.finish_parent();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometric_mean() {
+        let old = r#"{"benchmarks":{
+            "a":{"time":{"median":1,"mean":1}},
+            "b":{"time":{"median":4,"mean":4}},
+            "c":{"time":{"median":1,"mean":1}}
+        }}"#;
+        let new = r#"{"benchmarks":{
+            "a":{"time":{"median":2,"mean":2}},
+            "b":{"time":{"median":2,"mean":2}},
+            "d":{"time":{"median":1,"mean":1}}
+        }}"#;
+
+        let old = Baseline::parse("old", old).unwrap();
+        let new = Baseline::parse("new", new).unwrap();
+
+        // 2x slower and 2x faster cancel out.
+        assert_eq!(geometric_mean_summary(&old, &new), "+0.00% across 2 benchmarks");
+
+        let empty = Baseline::parse("empty", r#"{"benchmarks":{}}"#).unwrap();
+        assert_eq!(geometric_mean_summary(&empty, &new), "no common benchmarks");
+    }
+
+    #[test]
+    fn path_tree() {
+        let json = r#"{"benchmarks":{
+            "math::add":{"time":{}},
+            "math::sub::1":{"time":{}},
+            "math::sub::2":{"time":{}},
+            "io":{"time":{}}
+        }}"#;
+        let results = Baseline::parse("results", json).unwrap();
+
+        let mut tree = Vec::new();
+        for (segments, stats) in results.iter_segments() {
+            PathTree::insert(&mut tree, segments, stats);
+        }
+
+        fn names<'a>(tree: &[PathTree<'a>]) -> Vec<&'a str> {
+            tree.iter().map(|node| node.name).collect()
+        }
+
+        assert_eq!(names(&tree), ["math", "io"]);
+        assert_eq!(names(&tree[0].children), ["add", "sub"]);
+        assert_eq!(names(&tree[0].children[1].children), ["1", "2"]);
+        assert!(tree[0].stats.is_none());
+        assert!(tree[1].stats.is_some());
+
+        assert_eq!(PathTree::max_name_span(&tree, 0), 7);
+    }
+
+    #[test]
+    fn path_tree_segments() {
+        // Type names contain `::`, which only recorded segments can tell apart
+        // from path separators.
+        let json = r#"{"benchmarks":{
+            "vec::Vec<std::string::String>":{
+                "segments":["vec","Vec<std::string::String>"],
+                "time":{}
+            }
+        }}"#;
+        let results = Baseline::parse("results", json).unwrap();
+
+        let mut tree = Vec::new();
+        for (segments, stats) in results.iter_segments() {
+            PathTree::insert(&mut tree, segments, stats);
+        }
+
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].name, "vec");
+        assert_eq!(tree[0].children.len(), 1);
+        assert_eq!(tree[0].children[0].name, "Vec<std::string::String>");
+        assert!(tree[0].children[0].children.is_empty());
+    }
+}
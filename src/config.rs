@@ -14,6 +14,56 @@ impl FromStr for ParsedSeconds {
     }
 }
 
+/// Fraction parsed from either a percentage like `5%` or a number like `0.05`.
+#[derive(Clone, Copy)]
+pub(crate) struct ParsedFraction(pub f64);
+
+impl FromStr for ParsedFraction {
+    type Err = Box<dyn Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fraction = match s.strip_suffix('%') {
+            Some(percent) => f64::from_str(percent.trim_end())? / 100.0,
+            None => f64::from_str(s)?,
+        };
+
+        if fraction.is_finite() && fraction >= 0.0 {
+            Ok(Self(fraction))
+        } else {
+            Err(format!("expected non-negative number, found '{s}'").into())
+        }
+    }
+}
+
+/// Baseline name that is safe to use as a file name in
+/// `target/divan/baselines`.
+#[derive(Clone)]
+pub(crate) struct ParsedBaselineName(pub String);
+
+impl FromStr for ParsedBaselineName {
+    type Err = Box<dyn Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Names must not escape the baselines directory.
+        let is_valid = !s.is_empty()
+            && !s.contains("..")
+            && !s.chars().any(|ch| ch == '/' || ch == '\\' || std::path::is_separator(ch));
+
+        if is_valid {
+            Ok(Self(s.to_owned()))
+        } else {
+            Err(format!("expected name without path separators or '..', found '{s}'").into())
+        }
+    }
+}
+
+/// How outlier samples are treated when computing median and mean times.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutlierMode {
+    Include,
+    Exclude,
+}
+
 /// The primary action to perform.
 #[derive(Clone, Copy, Default)]
 pub(crate) enum Action {
@@ -182,3 +232,19 @@ impl SortingAttr {
         Ordering::Equal
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_baseline_name() {
+        for name in ["main", "v1.2", "feature-x_2"] {
+            assert_eq!(ParsedBaselineName::from_str(name).unwrap().0, name);
+        }
+
+        for name in ["", "..", "../main", "a/b", r"a\b", "/tmp/main"] {
+            assert!(ParsedBaselineName::from_str(name).is_err(), "{name}");
+        }
+    }
+}
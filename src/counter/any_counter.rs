@@ -87,6 +87,15 @@ impl KnownCounterKind {
     /// The maximum width for columns displaying counters.
     pub const MAX_COMMON_COLUMN_WIDTH: usize = "1.111 Kitem/s".len();
 
+    #[inline]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bytes => "bytes",
+            Self::Chars => "chars",
+            Self::Items => "items",
+        }
+    }
+
     #[inline]
     pub fn of<C: IntoCounter>() -> Self {
         let id = TypeId::of::<C::Counter>();
@@ -1,19 +1,40 @@
 #![allow(clippy::too_many_arguments)]
 
-use std::{borrow::Cow, cell::RefCell, fmt, num::NonZeroUsize, time::Duration};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    num::NonZeroUsize,
+    path::PathBuf,
+    ptr::NonNull,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
 
 use clap::ColorChoice;
 use regex::Regex;
 
 use crate::{
-    bench::BenchOptions,
-    config::{Action, Filter, ParsedSeconds, RunIgnored, SortingAttr},
+    bench::{BenchOptions, PrivSampling, Sampling},
+    config::{
+        Action, Filter, OutlierMode, ParsedBaselineName, ParsedFraction, ParsedSeconds, RunIgnored,
+        SortingAttr,
+    },
     counter::{
         BytesCount, BytesFormat, CharsCount, IntoCounter, ItemsCount, MaxCountUInt, PrivBytesFormat,
     },
     entry::{AnyBenchEntry, BenchEntryRunner, EntryTree},
+    report::{
+        self, baseline_path, Baseline, Column, CsvReporter, History, HistoryReporter, HtmlReporter,
+        JsonLinesReporter, JsonReporter, MarkdownReporter, MultiReporter, Node, NodeKind,
+        OutputFormat, PrivColumn, PrivOutputFormat, RegressionGate, Report, Reporter,
+        SaveBaselineReporter, TerseReporter, TreeBuilder,
+    },
+    stats::{Bootstrap, Stats},
     time::{FineDuration, Timer, TimerKind},
-    tree_painter::{TreeColumn, TreePainter},
+    tree_painter::{ColumnSet, TreeColumn, TreeExtras, TreePainter},
     util, Bencher,
 };
 
@@ -26,6 +47,20 @@ pub struct Divan {
     sorting_attr: SortingAttr,
     color: ColorChoice,
     bytes_format: BytesFormat,
+    format: OutputFormat,
+    columns: Option<Vec<Column>>,
+    html_path: Option<PathBuf>,
+    save_baseline: Option<String>,
+    baseline: Option<String>,
+    fail_on_regression: Option<f64>,
+    relative: bool,
+    histogram: bool,
+    append_history: Option<PathBuf>,
+    history_label: Option<String>,
+    show_trend: Option<usize>,
+    bootstrap: Bootstrap,
+    exclude_outliers: bool,
+    reporters: Mutex<Vec<Box<dyn Reporter + Send>>>,
     filters: Vec<Filter>,
     skip_filters: Vec<Filter>,
     run_ignored: RunIgnored,
@@ -44,6 +79,25 @@ pub(crate) struct SharedContext {
     ///
     /// `min_time` and `max_time` do not consider this as benchmarking time.
     pub bench_overhead: FineDuration,
+
+    /// Options for confidence intervals of iteration times.
+    pub bootstrap: Bootstrap,
+
+    /// Whether outlier samples are excluded from median and mean times.
+    pub exclude_outliers: bool,
+}
+
+/// Stats of entries compared against via `#[divan::bench(baseline = ...)]`.
+///
+/// This allows each baseline to be run only once, regardless of whether it is
+/// reached before or after the benchmarks compared against it.
+#[derive(Default)]
+struct BaselineStats {
+    /// Addresses of entries used as baselines.
+    entry_addrs: HashSet<NonNull<()>>,
+
+    /// Stats keyed by entry address, argument index, and thread count.
+    stats: RefCell<HashMap<(NonNull<()>, Option<usize>, NonZeroUsize), Stats>>,
 }
 
 impl fmt::Debug for Divan {
@@ -65,6 +119,33 @@ impl Divan {
         self.run_action(Action::Bench);
     }
 
+    /// Benchmark registered functions and return the collected results.
+    ///
+    /// Results are still output in the configured [format](Divan::format).
+    /// The returned [`Report`](crate::report::Report) allows for applying
+    /// custom pass/fail gates, merging runs, or post-processing results
+    /// without parsing output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn main() {
+    ///     let report = divan::Divan::from_args().run_benches_collect();
+    ///
+    ///     for leaf in report.leaves() {
+    ///         if let Some(stats) = leaf.stats() {
+    ///             println!("{}: {} ns", leaf.path(), stats.time_nanos().median);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[must_use = "use `run_benches` if the report is not needed"]
+    pub fn run_benches_collect(&self) -> Report {
+        let mut tree_builder = TreeBuilder::default();
+        _ = self.run_action_with(Action::Bench, Some(&mut tree_builder));
+        tree_builder.take_report()
+    }
+
     /// Test registered functions as if the `--test` flag was used.
     ///
     /// Unlike [`Divan::run_benches`], this runs each benchmarked function only
@@ -97,7 +178,35 @@ impl Divan {
         !self.run_ignored.should_run(ignored)
     }
 
+    /// Returns whether any output needs confidence intervals, which are costly
+    /// to estimate for every benchmark.
+    fn needs_confidence_intervals(&self, has_extra_reporter: bool) -> bool {
+        let has_ci_column = self.columns.as_deref().is_some_and(|columns| {
+            columns.iter().any(|column| matches!(column, Column::MedianCi | Column::MeanCi))
+        });
+
+        // Custom reporters and machine-readable formats provide every
+        // statistic.
+        has_ci_column
+            || has_extra_reporter
+            || self.save_baseline.is_some()
+            || matches!(
+                self.format,
+                OutputFormat::Json | OutputFormat::JsonLines | OutputFormat::Csv
+            )
+            || !self.reporters.lock().unwrap_or_else(PoisonError::into_inner).is_empty()
+    }
+
     pub(crate) fn run_action(&self, action: Action) {
+        if self.run_action_with(action, None) {
+            std::process::exit(1);
+        }
+    }
+
+    /// Performs `action`, also sending events to `extra_reporter`.
+    ///
+    /// Returns `true` if any benchmark regressed beyond its threshold.
+    fn run_action_with(&self, action: Action, extra_reporter: Option<&mut dyn Reporter>) -> bool {
         let mut tree: Vec<EntryTree> = if cfg!(miri) {
             // Miri does not work with our linker tricks.
             Vec::new()
@@ -128,7 +237,7 @@ impl Divan {
 
         // Quick exit without doing unnecessary work.
         if tree.is_empty() {
-            return;
+            return false;
         }
 
         // Sorting is after filtering to compare fewer elements.
@@ -160,39 +269,218 @@ impl Divan {
             } else {
                 FineDuration::default()
             },
+            bootstrap: if self.needs_confidence_intervals(extra_reporter.is_some()) {
+                self.bootstrap
+            } else {
+                Bootstrap { resamples: 0, ..self.bootstrap }
+            },
+            exclude_outliers: self.exclude_outliers,
         };
 
-        let column_widths = if action.is_bench() {
-            TreeColumn::ALL.map(|column| {
-                if column.is_last() {
-                    // The last column doesn't use padding.
-                    0
-                } else {
-                    EntryTree::common_column_width(&tree, column)
+        let baseline = self.baseline.as_ref().filter(|_| action.is_bench()).and_then(|name| {
+            match Baseline::load(name) {
+                Ok(baseline) => Some(baseline),
+                // A regression gate must not pass without its baseline.
+                Err(error) if self.fail_on_regression.is_some() => {
+                    eprintln!("error: Failed to load baseline '{name}': {error}");
+                    std::process::exit(1);
                 }
-            })
-        } else {
-            [0; TreeColumn::COUNT]
+                Err(error) => {
+                    eprintln!("warning: Failed to load baseline '{name}': {error}");
+                    None
+                }
+            }
+        });
+
+        let regression_gate = match (&baseline, self.fail_on_regression) {
+            (Some(baseline), Some(threshold)) => {
+                Some(RefCell::new(RegressionGate::new(baseline, threshold)))
+            }
+            (None, Some(_)) if action.is_bench() && self.baseline.is_none() => {
+                eprintln!("warning: '--fail-on-regression' requires '--baseline'");
+                None
+            }
+            _ => None,
         };
 
-        let tree_painter =
-            RefCell::new(TreePainter::new(EntryTree::max_name_span(&tree, 0), column_widths));
+        // The current run is shown along with previous runs.
+        let history = match (&self.append_history, self.show_trend) {
+            (Some(path), Some(runs)) if action.is_bench() => {
+                match History::load(path, runs.saturating_sub(1)) {
+                    Ok(history) => {
+                        if history.skipped_lines > 0 {
+                            eprintln!(
+                                "warning: Skipped {} malformed line{} of {}",
+                                history.skipped_lines,
+                                if history.skipped_lines == 1 { "" } else { "s" },
+                                path.display()
+                            );
+                        }
+                        Some((history, runs))
+                    }
+                    Err(error) => {
+                        eprintln!(
+                            "warning: Failed to load history from {}: {error}",
+                            path.display()
+                        );
+                        None
+                    }
+                }
+            }
+            (None, Some(_)) if action.is_bench() => {
+                eprintln!("warning: '--show-trend' requires '--append-history'");
+                None
+            }
+            _ => None,
+        };
+
+        let baseline_stats = BaselineStats {
+            entry_addrs: if action.is_bench() {
+                EntryTree::baseline_addrs(&tree)
+            } else {
+                HashSet::new()
+            },
+            stats: RefCell::default(),
+        };
+
+        let column_set = match &self.columns {
+            Some(columns) => ColumnSet::new(columns),
+            None => {
+                let mut column_set = ColumnSet::default();
+
+                // Linear sampling estimates time as the slope of a fit, whose
+                // quality is shown alongside it.
+                let is_linear = match self.bench_options.sampling {
+                    Some(sampling) => sampling == Sampling::Linear,
+                    None => EntryTree::any_bench_options(&tree, &|options| {
+                        options.sampling == Some(Sampling::Linear)
+                    }),
+                };
+                if is_linear {
+                    column_set.columns.extend([TreeColumn::Slope, TreeColumn::RSquared]);
+                }
+
+                column_set
+            }
+        };
+
+        let mut format_reporter: Box<dyn Reporter> = match self.format {
+            OutputFormat::Pretty => {
+                // Only benchmarking has stats to show in columns.
+                let tree_columns =
+                    if action.is_bench() { column_set.clone() } else { ColumnSet::empty() };
+
+                let has_extra_columns = baseline.is_some()
+                    || self.relative
+                    || history.is_some()
+                    || !baseline_stats.entry_addrs.is_empty();
+
+                let column_count = tree_columns.columns.len();
+                let column_widths = tree_columns
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &column)| {
+                        // The last column doesn't use padding, unless followed
+                        // by extra columns.
+                        if i == column_count - 1 && !has_extra_columns {
+                            0
+                        } else {
+                            EntryTree::common_column_width(&tree, column)
+                        }
+                    })
+                    .collect();
+
+                Box::new(TreePainter::new(
+                    EntryTree::max_name_span(&tree, 0),
+                    tree_columns,
+                    column_widths,
+                    self.bytes_format,
+                    TreeExtras {
+                        baselines: baseline.iter().collect(),
+                        has_relative: !baseline_stats.entry_addrs.is_empty(),
+                        relative_to_fastest: self.relative && action.is_bench(),
+                        trend: history.as_ref().map(|(history, runs)| (history, *runs)),
+                        histogram: self.histogram && action.is_bench(),
+                    },
+                ))
+            }
+            OutputFormat::Terse => Box::new(TerseReporter::new(action, self.run_ignored)),
+            OutputFormat::Json => Box::new(JsonReporter::new()),
+            OutputFormat::JsonLines => Box::new(JsonLinesReporter::new(action)),
+            OutputFormat::Csv => Box::new(CsvReporter::new()),
+            OutputFormat::Markdown => {
+                Box::new(MarkdownReporter::new(self.bytes_format, column_set.clone()))
+            }
+        };
 
-        self.run_tree(action, &tree, &shared_context, None, &tree_painter);
+        let mut html_reporter =
+            self.html_path.as_ref().filter(|_| action.is_bench()).map(|html_path| {
+                HtmlReporter::new(html_path.clone(), self.bytes_format, column_set.clone())
+            });
+
+        // Only save when benchmarking so that testing or listing does not
+        // overwrite a previously saved baseline.
+        let mut save_baseline_reporter = self
+            .save_baseline
+            .as_ref()
+            .filter(|_| action.is_bench())
+            .map(|name| SaveBaselineReporter::new(name.clone(), baseline_path(name)));
+
+        let mut history_reporter = self
+            .append_history
+            .as_ref()
+            .filter(|_| action.is_bench())
+            .map(|path| HistoryReporter::new(path.clone(), self.history_label.as_deref()));
+
+        let mut custom_reporters = self.reporters.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut reporters: Vec<&mut dyn Reporter> = vec![&mut *format_reporter];
+        reporters.extend(extra_reporter.map(|reporter| reporter as &mut dyn Reporter));
+        reporters.extend(html_reporter.as_mut().map(|reporter| reporter as &mut dyn Reporter));
+        reporters
+            .extend(save_baseline_reporter.as_mut().map(|reporter| reporter as &mut dyn Reporter));
+        reporters.extend(history_reporter.as_mut().map(|reporter| reporter as &mut dyn Reporter));
+        reporters.extend(
+            custom_reporters.iter_mut().map(|reporter| &mut **reporter as &mut dyn Reporter),
+        );
+
+        let mut reporter = MultiReporter(reporters);
+        reporter.start();
+        let reporter = RefCell::new(reporter);
+
+        self.run_tree(
+            action,
+            &tree,
+            "",
+            &shared_context,
+            None,
+            &reporter,
+            regression_gate.as_ref(),
+            &baseline_stats,
+        );
+
+        reporter.borrow_mut().finish();
+
+        regression_gate.is_some_and(|gate| gate.borrow().report())
     }
 
     fn run_tree(
         &self,
         action: Action,
-        tree: &[EntryTree],
+        tree: &[EntryTree<'static>],
+        parent_path: &str,
         shared_context: &SharedContext,
         parent_options: Option<&BenchOptions>,
-        tree_painter: &RefCell<TreePainter>,
+        reporter: &RefCell<MultiReporter>,
+        regression_gate: Option<&RefCell<RegressionGate>>,
+        baseline_stats: &BaselineStats,
     ) {
         for (i, child) in tree.iter().enumerate() {
             let is_last = i == tree.len() - 1;
 
             let name = child.display_name();
+            let path = report::join_path(parent_path, name);
 
             let child_options = child.bench_options();
 
@@ -212,17 +500,37 @@ impl Divan {
                     action,
                     *entry,
                     args.as_deref(),
+                    &path,
                     shared_context,
                     options,
-                    tree_painter,
+                    reporter,
+                    regression_gate,
+                    baseline_stats,
                     is_last,
                 ),
                 EntryTree::Parent { children, .. } => {
-                    tree_painter.borrow_mut().start_parent(name, is_last);
-
-                    self.run_tree(action, children, shared_context, options, tree_painter);
+                    let node = Node {
+                        name,
+                        path: &path,
+                        kind: child.node_kind(),
+                        is_last,
+                        baseline: None,
+                        thread_count: None,
+                    };
+                    reporter.borrow_mut().start_parent(&node);
+
+                    self.run_tree(
+                        action,
+                        children,
+                        &path,
+                        shared_context,
+                        options,
+                        reporter,
+                        regression_gate,
+                        baseline_stats,
+                    );
 
-                    tree_painter.borrow_mut().finish_parent();
+                    reporter.borrow_mut().finish_parent();
                 }
             }
         }
@@ -231,16 +539,26 @@ impl Divan {
     fn run_bench_entry(
         &self,
         action: Action,
-        bench_entry: AnyBenchEntry,
+        bench_entry: AnyBenchEntry<'static>,
         bench_arg_names: Option<&[&&str]>,
+        entry_path: &str,
         shared_context: &SharedContext,
         entry_options: Option<&BenchOptions>,
-        tree_painter: &RefCell<TreePainter>,
+        reporter: &RefCell<MultiReporter>,
+        regression_gate: Option<&RefCell<RegressionGate>>,
+        baseline_stats: &BaselineStats,
         is_last_entry: bool,
     ) {
         use crate::bench::BenchContext;
 
-        let entry_display_name = bench_entry.display_name();
+        let entry_node = Node {
+            name: bench_entry.display_name(),
+            path: entry_path,
+            kind: bench_entry.node_kind(),
+            is_last: is_last_entry,
+            baseline: None,
+            thread_count: None,
+        };
 
         // User runtime options override all other options.
         let options: BenchOptions;
@@ -253,15 +571,15 @@ impl Divan {
         };
 
         if self.should_ignore(options.ignore.unwrap_or_default()) {
-            tree_painter.borrow_mut().ignore_leaf(entry_display_name, is_last_entry);
+            reporter.borrow_mut().ignore_leaf(&entry_node);
             return;
         }
 
-        // Paint empty leaf when simply listing.
+        // Report empty leaf when simply listing.
         if action.is_list() {
-            let mut tree_painter = tree_painter.borrow_mut();
-            tree_painter.start_leaf(entry_display_name, is_last_entry);
-            tree_painter.finish_empty_leaf();
+            let mut reporter = reporter.borrow_mut();
+            reporter.start_leaf(&entry_node);
+            reporter.finish_empty_leaf();
             return;
         }
 
@@ -285,59 +603,120 @@ impl Divan {
         // Whether we should emit child branches for thread counts.
         let has_thread_branches = thread_counts.len() > 1;
 
-        let run_bench = |bench_display_name: &str,
-                         is_last_bench: bool,
+        // Whether the stats of this entry are needed for comparing other
+        // entries against.
+        let is_baseline = baseline_stats.entry_addrs.contains(&bench_entry.entry_addr());
+
+        let run_bench = |bench_node: &Node,
+                         arg: Option<(usize, &str)>,
                          with_bencher: &dyn Fn(Bencher)| {
+            let bench_display_name = bench_node.name;
+
             if has_thread_branches {
-                tree_painter.borrow_mut().start_parent(bench_display_name, is_last_bench);
-            } else {
-                tree_painter.borrow_mut().start_leaf(bench_display_name, is_last_bench);
+                reporter.borrow_mut().start_parent(bench_node);
             }
 
             for (i, &thread_count) in thread_counts.iter().enumerate() {
-                let is_last_thread_count =
-                    if has_thread_branches { i == thread_counts.len() - 1 } else { is_last_bench };
+                let is_last_thread_count = if has_thread_branches {
+                    i == thread_counts.len() - 1
+                } else {
+                    bench_node.is_last
+                };
+
+                // Run the baseline before starting the leaf so that its stats
+                // can be reported alongside.
+                let baseline = if shared_context.action.is_bench() {
+                    self.run_baseline(
+                        bench_entry,
+                        arg.map(|(_, arg_name)| arg_name),
+                        thread_count,
+                        shared_context,
+                        options,
+                        baseline_stats,
+                    )
+                } else {
+                    None
+                };
+
+                let thread_name: String;
+                let thread_path: String;
+                let leaf_node = if has_thread_branches {
+                    thread_name = format!("t={thread_count}");
+                    thread_path = report::join_path(bench_node.path, &thread_name);
+                    Node {
+                        path: &thread_path,
+                        name: &thread_name,
+                        kind: NodeKind::Threads(thread_count),
+                        is_last: is_last_thread_count,
+                        baseline: baseline.as_ref(),
+                        thread_count: Some(thread_count),
+                    }
+                } else {
+                    Node {
+                        baseline: baseline.as_ref(),
+                        thread_count: Some(thread_count),
+                        ..*bench_node
+                    }
+                };
+                reporter.borrow_mut().start_leaf(&leaf_node);
 
-                if has_thread_branches {
-                    tree_painter
-                        .borrow_mut()
-                        .start_leaf(&format!("t={thread_count}"), is_last_thread_count);
-                }
+                let stats_key =
+                    (bench_entry.entry_addr(), arg.map(|(index, _)| index), thread_count);
 
-                let mut bench_context = BenchContext::new(shared_context, options, thread_count);
-                with_bencher(Bencher::new(&mut bench_context));
+                // Reuse stats if this entry was already run as a baseline.
+                let cached_stats = if is_baseline {
+                    baseline_stats.stats.borrow().get(&stats_key).cloned()
+                } else {
+                    None
+                };
 
-                if !bench_context.did_run {
-                    eprintln!(
-                        "warning: No benchmark function registered for '{bench_display_name}'"
-                    );
-                }
+                let stats = match cached_stats {
+                    Some(stats) => Some(stats),
+                    None => {
+                        let mut bench_context =
+                            BenchContext::new(shared_context, options, thread_count);
+                        with_bencher(Bencher::new(&mut bench_context));
 
-                let should_compute_stats =
-                    bench_context.did_run && shared_context.action.is_bench();
+                        if !bench_context.did_run {
+                            eprintln!(
+                                "warning: No benchmark function registered for '{bench_display_name}'"
+                            );
+                        }
 
-                if should_compute_stats {
-                    let stats = bench_context.compute_stats();
-                    tree_painter.borrow_mut().finish_leaf(
-                        is_last_thread_count,
-                        &stats,
-                        self.bytes_format,
-                    );
+                        let should_compute_stats =
+                            bench_context.did_run && shared_context.action.is_bench();
+
+                        let stats = should_compute_stats.then(|| bench_context.compute_stats());
+
+                        if let (true, Some(stats)) = (is_baseline, &stats) {
+                            baseline_stats.stats.borrow_mut().insert(stats_key, stats.clone());
+                        }
+
+                        stats
+                    }
+                };
+
+                if let Some(stats) = stats {
+                    reporter.borrow_mut().finish_leaf(&stats);
+
+                    if let Some(gate) = regression_gate {
+                        gate.borrow_mut().check(&leaf_node, &stats, options.regression_threshold);
+                    }
                 } else {
-                    tree_painter.borrow_mut().finish_empty_leaf();
+                    reporter.borrow_mut().finish_empty_leaf();
                 }
             }
 
             if has_thread_branches {
-                tree_painter.borrow_mut().finish_parent();
+                reporter.borrow_mut().finish_parent();
             }
         };
 
         match bench_entry.bench_runner() {
-            BenchEntryRunner::Plain(bench) => run_bench(entry_display_name, is_last_entry, bench),
+            BenchEntryRunner::Plain(bench) => run_bench(&entry_node, None, bench),
 
             BenchEntryRunner::Args(bench_runner) => {
-                tree_painter.borrow_mut().start_parent(entry_display_name, is_last_entry);
+                reporter.borrow_mut().start_parent(&entry_node);
 
                 let bench_runner = bench_runner();
                 let orig_arg_names = bench_runner.arg_names();
@@ -347,14 +726,98 @@ impl Divan {
                     let is_last_arg = i == bench_arg_names.len() - 1;
                     let arg_index = util::slice_ptr_index(orig_arg_names, arg_name);
 
-                    run_bench(arg_name, is_last_arg, &|bencher| {
+                    let arg_node = Node {
+                        name: arg_name,
+                        path: &report::join_path(entry_path, arg_name),
+                        kind: NodeKind::Arg,
+                        is_last: is_last_arg,
+                        baseline: None,
+                        thread_count: None,
+                    };
+
+                    run_bench(&arg_node, Some((arg_index, arg_name)), &|bencher| {
                         bench_runner.bench(bencher, arg_index);
                     });
                 }
 
-                tree_painter.borrow_mut().finish_parent();
+                reporter.borrow_mut().finish_parent();
+            }
+        }
+    }
+
+    /// Returns the stats of the baseline of `bench_entry` for the same argument
+    /// and thread count, running the baseline if it has not been run yet.
+    fn run_baseline(
+        &self,
+        bench_entry: AnyBenchEntry<'static>,
+        arg_name: Option<&str>,
+        thread_count: NonZeroUsize,
+        shared_context: &SharedContext,
+        options: &BenchOptions,
+        baseline_stats: &BaselineStats,
+    ) -> Option<Stats> {
+        use crate::bench::BenchContext;
+
+        let baseline = bench_entry.baseline()?;
+
+        // Find the baseline argument with the same name.
+        let args_runner = match baseline.bench_runner() {
+            BenchEntryRunner::Plain(_) => None,
+            BenchEntryRunner::Args(bench_runner) => {
+                let bench_runner = bench_runner();
+                let arg_index =
+                    bench_runner.arg_names().iter().position(|&name| Some(name) == arg_name)?;
+
+                Some((bench_runner, arg_index))
+            }
+        };
+
+        let stats_key =
+            (baseline.entry_addr(), args_runner.as_ref().map(|(_, index)| *index), thread_count);
+
+        if let Some(stats) = baseline_stats.stats.borrow().get(&stats_key) {
+            return Some(stats.clone());
+        }
+
+        // The baseline is run with the same options as the benchmark, except
+        // for options set on the baseline itself. User runtime options still
+        // override all other options.
+        let entry_options: BenchOptions;
+        let runtime_options: BenchOptions;
+        let options: &BenchOptions = match baseline.meta().bench_options() {
+            None => options,
+            Some(baseline_options) => {
+                entry_options = baseline_options.overwrite(options);
+                runtime_options = self.bench_options.overwrite(&entry_options);
+                &runtime_options
             }
+        };
+
+        let mut bench_context = BenchContext::new(shared_context, options, thread_count);
+        let bencher = Bencher::new(&mut bench_context);
+
+        match (baseline.bench_runner(), &args_runner) {
+            (_, Some((bench_runner, arg_index))) => bench_runner.bench(bencher, *arg_index),
+            (BenchEntryRunner::Plain(bench), None) => bench(bencher),
+            (BenchEntryRunner::Args(_), None) => unreachable!(),
+        }
+
+        if !bench_context.did_run {
+            return None;
         }
+
+        let stats = bench_context.compute_stats();
+        baseline_stats.stats.borrow_mut().insert(stats_key, stats.clone());
+        Some(stats)
+    }
+}
+
+/// Validates a baseline name passed to a `Divan` builder method.
+#[track_caller]
+fn parse_baseline_name(name: String) -> String {
+    match ParsedBaselineName::from_str(&name) {
+        Ok(ParsedBaselineName(name)) => name,
+        Err(error) => panic!("invalid baseline name: {error}"),
     }
 }
 
@@ -413,6 +876,14 @@ impl Divan {
             Action::Bench
         };
 
+        if matches.get_flag("relative") {
+            self.relative = true;
+        }
+
+        if matches.get_flag("histogram") {
+            self.histogram = true;
+        }
+
         if let Some(&color) = matches.get_one("color") {
             self.color = color;
         }
@@ -443,6 +914,10 @@ impl Divan {
             self.bench_options.sample_size = Some(sample_size);
         }
 
+        if let Some(&PrivSampling(sampling)) = matches.get_one("sampling") {
+            self.bench_options.sampling = Some(sampling);
+        }
+
         if let Some(thread_counts) = matches.get_many::<usize>("threads") {
             let mut threads: Vec<usize> = thread_counts.copied().collect();
             threads.sort_unstable();
@@ -450,6 +925,14 @@ impl Divan {
             self.bench_options.threads = Some(Cow::Owned(threads));
         }
 
+        if let Some(&ParsedSeconds(warmup_time)) = matches.get_one("warmup-time") {
+            self.bench_options.warmup_time = Some(warmup_time);
+        }
+
+        if let Some(&warmup_iters) = matches.get_one("warmup-iters") {
+            self.bench_options.warmup_iters = Some(warmup_iters);
+        }
+
         if let Some(&ParsedSeconds(min_time)) = matches.get_one("min-time") {
             self.bench_options.min_time = Some(min_time);
         }
@@ -458,12 +941,24 @@ impl Divan {
             self.bench_options.max_time = Some(max_time);
         }
 
+        if let Some(&ParsedSeconds(max_sample_time)) = matches.get_one("max-sample-time") {
+            self.bench_options.max_sample_time = Some(max_sample_time);
+        }
+
         if let Some(mut skip_ext_time) = matches.get_many::<bool>("skip-ext-time") {
             // If the option is present without a value, then it's `true`.
             self.bench_options.skip_ext_time =
                 Some(matches!(skip_ext_time.next(), Some(true) | None));
         }
 
+        if let Some(&ParsedFraction(precision)) = matches.get_one("target-precision") {
+            if precision > 0.0 {
+                self.bench_options.target_precision = Some(precision);
+            } else {
+                eprintln!("warning: Ignoring '--target-precision' of 0%");
+            }
+        }
+
         if let Some(&count) = matches.get_one::<MaxCountUInt>("items-count") {
             self.counter_mut(ItemsCount::new(count));
         }
@@ -476,6 +971,61 @@ impl Divan {
             self.bytes_format = bytes_format;
         }
 
+        if let Some(&PrivOutputFormat(format)) = matches.get_one("format") {
+            self.format = format;
+        }
+
+        if let Some(columns) = matches.get_many::<PrivColumn>("columns") {
+            self.columns = Some(columns.map(|&PrivColumn(column)| column).collect());
+        }
+
+        if let Some(mut html_path) = matches.get_many::<PathBuf>("html") {
+            // If the option is present without a value, then use the default.
+            self.html_path = Some(
+                html_path.next().cloned().unwrap_or_else(|| util::divan_dir().join("report.html")),
+            );
+        }
+
+        if let Some(ParsedBaselineName(name)) = matches.get_one("baseline") {
+            self.baseline = Some(name.clone());
+        }
+
+        if let Some(&ParsedFraction(threshold)) = matches.get_one("fail-on-regression") {
+            self.fail_on_regression = Some(threshold);
+        }
+
+        if let Some(ParsedBaselineName(name)) = matches.get_one("save-baseline") {
+            self.save_baseline = Some(name.clone());
+        }
+
+        if let Some(path) = matches.get_one::<PathBuf>("append-history") {
+            self.append_history = Some(path.clone());
+        }
+
+        if let Some(label) = matches.get_one::<String>("history-label") {
+            self.history_label = Some(label.clone());
+        }
+
+        if let Some(&runs) = matches.get_one::<usize>("show-trend") {
+            self.show_trend = Some(runs);
+        }
+
+        if let Some(&ParsedFraction(level)) = matches.get_one("confidence-level") {
+            if level > 0.0 && level < 1.0 {
+                self.bootstrap.confidence_level = level;
+            } else {
+                eprintln!("warning: Ignoring '--confidence-level' outside of 0% and 100%");
+            }
+        }
+
+        if let Some(&resamples) = matches.get_one::<u32>("bootstrap-resamples") {
+            self.bootstrap.resamples = resamples;
+        }
+
+        if let Some(&mode) = matches.get_one::<OutlierMode>("outliers") {
+            self.exclude_outliers = mode == OutlierMode::Exclude;
+        }
+
         if let Some(&count) = matches.get_one::<MaxCountUInt>("chars-count") {
             self.counter_mut(CharsCount::new(count));
         }
@@ -497,6 +1047,316 @@ impl Divan {
         self
     }
 
+    /// Sets the format in which results are output.
+    ///
+    /// With [`OutputFormat::Terse`], one line is printed per benchmark with its
+    /// full path and its median and mean times, such as
+    /// `math::add: median 0.5 ns, mean 0.51 ns`. When listing benchmarks, lines
+    /// use the libtest format of `math::add: benchmark`, which allows tools like
+    /// IDEs and [`cargo-nextest`](https://nexte.st) to discover benchmarks.
+    ///
+    /// With [`OutputFormat::Json`], a single JSON document is printed to
+    /// stdout once all benchmarks have run. It contains the tree of entries,
+    /// where each node has a `name` and a `kind` (`group`, `bench`, `type`,
+    /// `const`, `arg`, or `threads`). Parents have `children`, ignored leaves
+    /// have `"ignored": true`, and benchmarked leaves have `stats` with times
+    /// in nanoseconds and throughputs per second.
+    ///
+    /// With [`OutputFormat::JsonLines`], a JSON object is printed on its own
+    /// line for each event as it happens, allowing progress to be shown during
+    /// long runs. Each object has an `event` of `suite_started`,
+    /// `group_started`, `bench_started`, `bench_finished`, `bench_ignored`, or
+    /// `suite_finished`. Node events have a `name`, `path`, and `kind`, and
+    /// `bench_finished` has the same `stats` as [`OutputFormat::Json`].
+    ///
+    /// With [`OutputFormat::Csv`], a header and one row per benchmarked leaf
+    /// are printed to stdout once all benchmarks have run. Each row starts with
+    /// the leaf's full path (including `args`, `types`, `consts`, and `t=N`
+    /// thread counts), followed by times in nanoseconds, sample and iteration
+    /// counts, and then throughput and allocation columns for the counters and
+    /// allocation operations that any benchmark reported.
+    ///
+    /// With [`OutputFormat::Markdown`], a GitHub-flavored Markdown table is
+    /// printed for each top-level group, with a row for each benchmarked leaf.
+    /// Values are formatted the same as in [`OutputFormat::Pretty`], making it
+    /// suitable for pull request comments and CI job summaries.
+    ///
+    /// This option is equivalent to the `--format` CLI argument or
+    /// `DIVAN_FORMAT` environment variable.
+    #[must_use]
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the columns of human-readable output, in the order they are shown.
+    ///
+    /// By default, [`fastest`](Column::Fastest), [`slowest`](Column::Slowest),
+    /// [`median`](Column::Median), [`mean`](Column::Mean),
+    /// [`samples`](Column::Samples), and [`iters`](Column::Iters) are shown,
+    /// along with [throughput](Column::Throughput) of
+    /// [counters](crate::counter) and [allocation](Column::Alloc) stats when
+    /// [`AllocProfiler`](crate::AllocProfiler) is used. Percentiles and
+    /// dispersion such as [`p99`](Column::P99) and [`std_dev`](Column::StdDev)
+    /// can be shown to see tail latency and noise, and
+    /// [`median_ci`](Column::MedianCi) to see the margin of error of the
+    /// median.
+    ///
+    /// Throughput and allocation stats are shown in rows below each
+    /// benchmark's time stats, within each selected time column such as
+    /// `median`, so their position in `columns` does not matter.
+    ///
+    /// This applies to [`OutputFormat::Pretty`] and [`OutputFormat::Markdown`]
+    /// output, as well as [HTML reports](Divan::html). Machine-readable
+    /// formats always include every statistic.
+    ///
+    /// This option is equivalent to the `--columns` CLI argument or
+    /// `DIVAN_COLUMNS` environment variable, which accept comma-separated
+    /// names like `median,p99,mean,throughput,alloc`.
+    #[must_use]
+    pub fn columns(mut self, columns: impl IntoIterator<Item = Column>) -> Self {
+        self.columns = Some(columns.into_iter().collect());
+        self
+    }
+
+    /// Writes a self-contained HTML report to `path` after running benchmarks.
+    ///
+    /// The report renders the same tree as the terminal output, with
+    /// collapsible groups, sortable columns, and a chart of each benchmark's
+    /// samples in the order they were taken.
+    ///
+    /// This option is equivalent to the `--html=<PATH>` CLI argument or
+    /// `DIVAN_HTML` environment variable. If `--html` is provided without a
+    /// path, the report is written to `target/divan/report.html`.
+    #[must_use]
+    pub fn html(mut self, path: impl Into<PathBuf>) -> Self {
+        self.html_path = Some(path.into());
+        self
+    }
+
+    /// Saves the statistics and samples of each benchmark as the baseline
+    /// called `name`.
+    ///
+    /// The baseline is written as JSON to
+    /// `target/divan/baselines/<name>.json`, keyed by each benchmark's full
+    /// path, including `args`, `types`, and `consts`. Keys always end with the
+    /// `t=N` thread count, even when only one is used, so that runs with
+    /// different [`threads`](macro@crate::bench#threads) are not compared.
+    /// Saving to an existing name replaces that baseline. Nothing is saved
+    /// when testing or listing benchmarks.
+    ///
+    /// This option is equivalent to the `--save-baseline` CLI argument or
+    /// `DIVAN_SAVE_BASELINE` environment variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty or contains path separators or `..`.
+    #[must_use]
+    #[track_caller]
+    pub fn save_baseline(mut self, name: impl Into<String>) -> Self {
+        self.save_baseline = Some(parse_baseline_name(name.into()));
+        self
+    }
+
+    /// Compares results against the baseline called `name`, which was
+    /// previously saved with [`Divan::save_baseline`].
+    ///
+    /// An extra column is added to the tree output, showing for each
+    /// benchmark the change in median and mean times, along with whether it
+    /// became faster or slower. A change is only considered significant if
+    /// the median changed by at least 2% and the samples differ according to
+    /// the [Mann-Whitney U test](https://en.wikipedia.org/wiki/Mann%E2%80%93Whitney_U_test).
+    /// Benchmarks are matched by full path, and ones not in the baseline are
+    /// shown as "(new)".
+    ///
+    /// This option is equivalent to the `--baseline` CLI argument or
+    /// `DIVAN_BASELINE` environment variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty or contains path separators or `..`.
+    #[must_use]
+    #[track_caller]
+    pub fn baseline(mut self, name: impl Into<String>) -> Self {
+        self.baseline = Some(parse_baseline_name(name.into()));
+        self
+    }
+
+    /// Exits with a non-zero status if a benchmark became slower than the
+    /// [baseline](Divan::baseline) by more than `threshold`, such as `0.05`
+    /// for 5%.
+    ///
+    /// Only statistically significant changes in median time are considered,
+    /// as described in [`Divan::baseline`]. A summary of the offending
+    /// benchmark paths is printed once all benchmarks have run. The threshold
+    /// can be overridden per benchmark with
+    /// [`#[divan::bench(regression_threshold = ...)]`](macro@crate::bench#regression_threshold).
+    /// If the baseline cannot be loaded, this exits with a non-zero status
+    /// before running benchmarks.
+    ///
+    /// This option is equivalent to the `--fail-on-regression` CLI argument or
+    /// `DIVAN_FAIL_ON_REGRESSION` environment variable, which accept
+    /// percentages like `5%`.
+    #[must_use]
+    pub fn fail_on_regression(mut self, threshold: f64) -> Self {
+        self.fail_on_regression = Some(threshold);
+        self
+    }
+
+    /// Shows each benchmark's median time as a multiple of its fastest
+    /// sibling, such as other [`types`](macro@crate::bench#types),
+    /// [`consts`](macro@crate::bench#consts), [`args`](macro@crate::bench#args),
+    /// or thread counts.
+    ///
+    /// This adds an extra column to [`OutputFormat::Pretty`] output, which is
+    /// printed once all siblings have finished rather than as each benchmark
+    /// finishes.
+    ///
+    /// This option is equivalent to the `--relative` CLI argument or
+    /// `DIVAN_RELATIVE` environment variable.
+    #[must_use]
+    pub fn relative(mut self, yes: bool) -> Self {
+        self.relative = yes;
+        self
+    }
+
+    /// Draws a histogram of sample times below each benchmark, which makes
+    /// bimodal or long-tailed distributions visible.
+    ///
+    /// This adds a line to [`OutputFormat::Pretty`] output, where each
+    /// character is a bin of equal width between the fastest and slowest
+    /// sample, and its height is the number of samples in that bin.
+    ///
+    /// This option is equivalent to the `--histogram` CLI argument or
+    /// `DIVAN_HISTOGRAM` environment variable.
+    #[must_use]
+    pub fn histogram(mut self, yes: bool) -> Self {
+        self.histogram = yes;
+        self
+    }
+
+    /// Appends the statistics of each benchmark as a line to the JSON-lines
+    /// history file at `path`, which is created if it does not exist.
+    ///
+    /// Each line records the Unix `timestamp` of the run, the `git_head`
+    /// commit read from the repository's `.git` directory, the
+    /// [label](Divan::history_label) if set, and `benchmarks` keyed by full
+    /// path in the same format as [saved baselines](Divan::save_baseline).
+    /// Nothing is appended when testing or listing benchmarks.
+    ///
+    /// This option is equivalent to the `--append-history` CLI argument or
+    /// `DIVAN_APPEND_HISTORY` environment variable.
+    #[must_use]
+    pub fn append_history(mut self, path: impl Into<PathBuf>) -> Self {
+        self.append_history = Some(path.into());
+        self
+    }
+
+    /// Records `label` with the run appended by [`Divan::append_history`],
+    /// such as the name of the machine or change being measured.
+    ///
+    /// This option is equivalent to the `--history-label` CLI argument or
+    /// `DIVAN_HISTORY_LABEL` environment variable.
+    #[must_use]
+    pub fn history_label(mut self, label: impl Into<String>) -> Self {
+        self.history_label = Some(label.into());
+        self
+    }
+
+    /// Shows a sparkline of each benchmark's median time across the last
+    /// `runs` runs in the [history](Divan::append_history) file, including the
+    /// current run.
+    ///
+    /// This adds an extra column to [`OutputFormat::Pretty`] output, which
+    /// helps spot slow drift that a comparison against a single
+    /// [baseline](Divan::baseline) misses. Runs that did not include a
+    /// benchmark are skipped in its sparkline.
+    ///
+    /// This option is equivalent to the `--show-trend=<N>` CLI argument or
+    /// `DIVAN_SHOW_TREND` environment variable. If `--show-trend` is provided
+    /// without a value, the last 10 runs are shown.
+    #[must_use]
+    pub fn show_trend(mut self, runs: usize) -> Self {
+        self.show_trend = Some(runs);
+        self
+    }
+
+    /// Sets the confidence level of intervals around median and mean times,
+    /// such as `0.99` for 99%. This is 95% by default.
+    ///
+    /// Intervals are estimated by bootstrapping: samples are resampled with
+    /// replacement [many times](Divan::bootstrap_resamples), and the interval
+    /// covers this fraction of the resulting medians or means. They can be
+    /// shown with the `median_ci` and `mean_ci` [columns](Divan::columns), and
+    /// are provided by
+    /// [`Stats::median_ci_nanos`](crate::report::Stats::median_ci_nanos) and
+    /// [`Stats::mean_ci_nanos`](crate::report::Stats::mean_ci_nanos).
+    ///
+    /// Since bootstrapping takes time, intervals are only estimated when they
+    /// are shown or output: in these columns, in machine-readable formats and
+    /// saved baselines, or to [custom reporters](Divan::reporter).
+    ///
+    /// This option is equivalent to the `--confidence-level` CLI argument or
+    /// `DIVAN_CONFIDENCE_LEVEL` environment variable, which accept percentages
+    /// like `99%`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is not between 0 and 1.
+    #[must_use]
+    pub fn confidence_level(mut self, level: f64) -> Self {
+        assert!(level > 0.0 && level < 1.0, "confidence level must be between 0 and 1");
+        self.bootstrap.confidence_level = level;
+        self
+    }
+
+    /// Sets the number of times samples are resampled to estimate
+    /// [confidence intervals](Divan::confidence_level). This is 10000 by
+    /// default.
+    ///
+    /// More resamples give more stable intervals at the cost of computing them
+    /// for longer. Intervals are not estimated if this is 0.
+    ///
+    /// This option is equivalent to the `--bootstrap-resamples` CLI argument or
+    /// `DIVAN_BOOTSTRAP_RESAMPLES` environment variable.
+    #[must_use]
+    pub fn bootstrap_resamples(mut self, resamples: u32) -> Self {
+        self.bootstrap.resamples = resamples;
+        self
+    }
+
+    /// Excludes outlier samples from median and mean times.
+    ///
+    /// Samples are outliers if their iteration time is beyond Tukey's fences:
+    /// 1.5 interquartile ranges below the first quartile or above the third
+    /// quartile, or 3 for severe outliers. These are often caused by
+    /// interference such as preemption on shared machines. Fastest, slowest,
+    /// and percentile times always include every sample.
+    ///
+    /// Outliers are counted regardless, which can be shown with the
+    /// [`outliers`](Column::Outliers) [column](Divan::columns) and are
+    /// provided by [`Stats::outliers`](crate::report::Stats::outliers).
+    ///
+    /// This option is equivalent to the `--outliers=exclude` CLI argument or
+    /// `DIVAN_OUTLIERS=exclude` environment variable.
+    #[must_use]
+    pub fn exclude_outliers(mut self, exclude: bool) -> Self {
+        self.exclude_outliers = exclude;
+        self
+    }
+
+    /// Sends benchmark events to `reporter` in addition to the output selected
+    /// by [`Divan::format`].
+    ///
+    /// This can be called multiple times to register multiple reporters, which
+    /// receive events in the order they were registered. See
+    /// [`divan::report`](crate::report) for an example.
+    #[must_use]
+    pub fn reporter(self, reporter: impl Reporter + Send + 'static) -> Self {
+        self.reporters.lock().unwrap_or_else(PoisonError::into_inner).push(Box::new(reporter));
+        self
+    }
+
     /// Also run benchmarks marked [`#[ignore]`](https://doc.rust-lang.org/reference/attributes/testing.html#the-ignore-attribute).
     ///
     /// This option is equivalent to the `--include-ignored` CLI argument.
@@ -627,6 +1487,18 @@ impl Divan {
         self
     }
 
+    /// Sets how the number of iterations inside each sample is chosen.
+    ///
+    /// See [`#[divan::bench(sampling = ...)]`](macro@crate::bench#sampling)
+    /// for more info.
+    ///
+    /// This option is equivalent to the `--sampling` CLI argument.
+    #[inline]
+    pub fn sampling(mut self, sampling: Sampling) -> Self {
+        self.bench_options.sampling = Some(sampling);
+        self
+    }
+
     /// Run across multiple threads.
     ///
     /// This enables you to measure contention on [atomics and
@@ -649,6 +1521,24 @@ impl Divan {
         self
     }
 
+    /// Sets the minimum time spent running a function before measuring it.
+    ///
+    /// This option is equivalent to the `--warmup-time` CLI argument.
+    #[inline]
+    pub fn warmup_time(mut self, time: Duration) -> Self {
+        self.bench_options.warmup_time = Some(time);
+        self
+    }
+
+    /// Sets the minimum number of iterations run before measuring a function.
+    ///
+    /// This option is equivalent to the `--warmup-iters` CLI argument.
+    #[inline]
+    pub fn warmup_iters(mut self, count: u32) -> Self {
+        self.bench_options.warmup_iters = Some(count);
+        self
+    }
+
     /// Sets the time floor for benchmarking a function.
     ///
     /// This option is equivalent to the `--min-time` CLI argument.
@@ -667,6 +1557,17 @@ impl Divan {
         self
     }
 
+    /// Sets the wall time budget for a single sample when choosing sample size,
+    /// including time external to benchmarked functions. This is 10
+    /// milliseconds by default.
+    ///
+    /// This option is equivalent to the `--max-sample-time` CLI argument.
+    #[inline]
+    pub fn max_sample_time(mut self, time: Duration) -> Self {
+        self.bench_options.max_sample_time = Some(time);
+        self
+    }
+
     /// When accounting for `min_time` or `max_time`, skip time external to
     /// benchmarked functions.
     ///
@@ -676,6 +1577,25 @@ impl Divan {
         self.bench_options.skip_ext_time = Some(skip);
         self
     }
+
+    /// Keeps sampling until the confidence interval around the median time is
+    /// within a relative margin, such as `0.01` for ±1%, or until
+    /// [`max_time`](Self::max_time).
+    ///
+    /// See [`#[divan::bench(target_precision = ...)]`](macro@crate::bench#target_precision)
+    /// for more info.
+    ///
+    /// This option is equivalent to the `--target-precision` CLI argument.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not positive.
+    #[inline]
+    pub fn target_precision(mut self, precision: f64) -> Self {
+        assert!(precision > 0.0 && precision.is_finite(), "target precision must be positive");
+        self.bench_options.target_precision = Some(precision);
+        self
+    }
 }
 
 /// Use [`Counter`s](crate::counter::Counter) to get throughput across all
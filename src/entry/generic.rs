@@ -76,7 +76,6 @@ pub struct EntryType {
     get_type_name: fn() -> &'static str,
 
     /// [`std::any::TypeId::of`].
-    #[allow(dead_code)]
     get_type_id: fn() -> TypeId,
 }
 
@@ -90,6 +89,10 @@ impl EntryType {
         (self.get_type_name)()
     }
 
+    pub(crate) fn type_id(&self) -> TypeId {
+        (self.get_type_id)()
+    }
+
     pub(crate) fn display_name(&self) -> &'static str {
         let mut type_name = self.raw_name();
 
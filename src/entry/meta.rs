@@ -1,6 +1,6 @@
 use std::sync::OnceLock;
 
-use crate::bench::BenchOptions;
+use crate::{bench::BenchOptions, entry::EntryType};
 
 /// Metadata common to `#[divan::bench]` and `#[divan::bench_group]`.
 pub struct EntryMeta {
@@ -23,6 +23,13 @@ pub struct EntryMeta {
 
     /// Cached `BenchOptions`.
     pub cached_bench_options: OnceLock<BenchOptions<'static>>,
+
+    /// The entry to compare against, set via `#[divan::bench(baseline = ...)]`.
+    pub baseline: Option<&'static EntryMeta>,
+
+    /// The generic type whose instances to compare against, set via
+    /// `#[divan::bench(baseline_type = ...)]`.
+    pub baseline_type: Option<EntryType>,
 }
 
 /// Where an entry is located.
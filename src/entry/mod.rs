@@ -1,6 +1,6 @@
-use std::ptr::NonNull;
+use std::ptr::{self, NonNull};
 
-use crate::{bench::BenchArgsRunner, Bencher};
+use crate::{bench::BenchArgsRunner, report::NodeKind, Bencher};
 
 mod generic;
 mod list;
@@ -123,4 +123,80 @@ impl<'a> AnyBenchEntry<'a> {
             Self::GenericBench(entry) => entry.display_name(),
         }
     }
+
+    /// Returns what this entry represents when reported.
+    #[inline]
+    pub fn node_kind(self) -> NodeKind {
+        match self {
+            Self::Bench(_) => NodeKind::Bench,
+            Self::GenericBench(GenericBenchEntry { const_value: Some(_), .. }) => NodeKind::Const,
+            Self::GenericBench(_) => NodeKind::Type,
+        }
+    }
+
+    /// Returns the generic type and `const` value of this entry.
+    #[inline]
+    fn generic_values(self) -> (Option<&'a EntryType>, Option<&'a EntryConst>) {
+        match self {
+            Self::Bench(_) => (None, None),
+            Self::GenericBench(entry) => (entry.ty.as_ref(), entry.const_value.as_ref()),
+        }
+    }
+}
+
+impl AnyBenchEntry<'static> {
+    /// Returns the entry to compare against, set via
+    /// `#[divan::bench(baseline = ...)]` or
+    /// `#[divan::bench(baseline_type = ...)]`.
+    ///
+    /// If the baseline is generic, this finds the instance with the same type
+    /// and `const` value as this entry.
+    pub fn baseline(self) -> Option<Self> {
+        let (ty, const_value) = self.generic_values();
+
+        // Only generic values used by the baseline need to match.
+        let matches = |baseline: &GenericBenchEntry, ty: Option<&EntryType>| {
+            let is_same_type = match (&baseline.ty, ty) {
+                (None, _) => true,
+                (Some(a), Some(b)) => a.type_id() == b.type_id(),
+                (Some(_), None) => false,
+            };
+
+            let is_same_const = match (&baseline.const_value, const_value) {
+                (None, _) => true,
+                (Some(a), Some(b)) => a.name() == b.name(),
+                (Some(_), None) => false,
+            };
+
+            is_same_type && is_same_const
+        };
+
+        if let Self::GenericBench(entry) = self {
+            if let Some(baseline_type) = &entry.group.meta.baseline_type {
+                let baseline = entry
+                    .group
+                    .generic_benches_iter()
+                    .find(|baseline| matches(baseline, Some(baseline_type)))?;
+
+                // Comparing against itself is meaningless.
+                return (!ptr::eq(baseline, entry)).then_some(Self::GenericBench(baseline));
+            }
+        }
+
+        let baseline_meta = self.meta().baseline?;
+
+        // Comparing against itself is meaningless.
+        if ptr::eq(baseline_meta, self.meta()) {
+            return None;
+        }
+
+        if let Some(entry) = BENCH_ENTRIES.iter().find(|entry| ptr::eq(&entry.meta, baseline_meta))
+        {
+            return Some(Self::Bench(entry));
+        }
+
+        let group = GROUP_ENTRIES.iter().find(|group| ptr::eq(&group.meta, baseline_meta))?;
+
+        group.generic_benches_iter().find(|baseline| matches(baseline, ty)).map(Self::GenericBench)
+    }
 }
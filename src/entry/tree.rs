@@ -1,10 +1,11 @@
-use std::{cmp::Ordering, ptr::NonNull};
+use std::{cmp::Ordering, collections::HashSet, ptr::NonNull};
 
 use crate::{
     bench::{BenchOptions, DEFAULT_SAMPLE_COUNT},
     config::SortingAttr,
     counter::KnownCounterKind,
     entry::{AnyBenchEntry, EntryLocation, EntryMeta, GenericBenchEntry, GroupEntry},
+    report::NodeKind,
     tree_painter::TreeColumn,
 };
 
@@ -89,6 +90,26 @@ impl<'a> EntryTree<'a> {
             return KnownCounterKind::MAX_COMMON_COLUMN_WIDTH;
         }
 
+        // Iteration counts depend on tuning, but most fit within 7 digits.
+        if column == TreeColumn::Iters {
+            return 7;
+        }
+
+        // Margins are usually at most as wide as "± 12.34%".
+        if column.is_ci() {
+            return "± 12.34%".chars().count();
+        }
+
+        // Goodness of fit is formatted like "0.9876".
+        if column == TreeColumn::RSquared {
+            return "0.9876".len();
+        }
+
+        // Outlier counts are usually at most as wide as "12 (12 severe)".
+        if column == TreeColumn::Outliers {
+            return "12 (12 severe)".len();
+        }
+
         tree.iter()
             .map(|tree| {
                 let Some(options) = tree.bench_options() else {
@@ -101,8 +122,7 @@ impl<'a> EntryTree<'a> {
                         1 + sample_count.checked_ilog10().unwrap_or_default() as usize
                     }
 
-                    // Iters is the last column, so it does not need pad width.
-                    // All other columns are time stats handled previously.
+                    // All other columns are handled previously.
                     _ => 0,
                 };
 
@@ -112,6 +132,14 @@ impl<'a> EntryTree<'a> {
             .unwrap_or_default()
     }
 
+    /// Returns `true` if any node in `tree` has options satisfying `predicate`.
+    pub fn any_bench_options(tree: &[Self], predicate: &impl Fn(&BenchOptions) -> bool) -> bool {
+        tree.iter().any(|node| {
+            node.bench_options().is_some_and(predicate)
+                || Self::any_bench_options(node.children(), predicate)
+        })
+    }
+
     /// Inserts the benchmark group into a tree.
     ///
     /// Groups are inserted after tree construction because it prevents having
@@ -320,6 +348,30 @@ impl<'a> EntryTree<'a> {
         }
     }
 
+    /// Returns what this node represents when reported.
+    pub fn node_kind(&self) -> NodeKind {
+        match self {
+            Self::Leaf { entry, .. } => entry.node_kind(),
+
+            // Generic benchmarks are parents to their instances.
+            Self::Parent { group: Some(GroupEntry { generic_benches: Some(_), .. }), .. } => {
+                NodeKind::Bench
+            }
+
+            // Generic types are parents to their `consts` instances.
+            Self::Parent { group: None, children, .. }
+                if !children.is_empty()
+                    && children.iter().all(|child| {
+                        matches!(child, Self::Leaf { entry: AnyBenchEntry::GenericBench(_), .. })
+                    }) =>
+            {
+                NodeKind::Type
+            }
+
+            Self::Parent { .. } => NodeKind::Group,
+        }
+    }
+
     pub fn meta(&self) -> Option<&'a EntryMeta> {
         match self {
             Self::Parent { group, .. } => Some(&(*group)?.meta),
@@ -402,3 +454,24 @@ impl<'a> EntryTree<'a> {
         }
     }
 }
+
+impl EntryTree<'static> {
+    /// Returns the addresses of entries that leaves in `tree` are compared
+    /// against via `#[divan::bench(baseline = ...)]`.
+    pub fn baseline_addrs(tree: &[Self]) -> HashSet<NonNull<()>> {
+        fn insert(tree: &[EntryTree<'static>], addrs: &mut HashSet<NonNull<()>>) {
+            for child in tree {
+                match child {
+                    EntryTree::Leaf { entry, .. } => {
+                        addrs.extend(entry.baseline().map(AnyBenchEntry::entry_addr));
+                    }
+                    EntryTree::Parent { children, .. } => insert(children, addrs),
+                }
+            }
+        }
+
+        let mut addrs = HashSet::new();
+        insert(tree, &mut addrs);
+        addrs
+    }
+}
@@ -22,7 +22,9 @@ mod time;
 mod tree_painter;
 mod util;
 
+pub mod compare;
 pub mod counter;
+pub mod report;
 
 /// Prevents compiler optimizations on a value.
 ///
@@ -116,7 +118,12 @@ pub mod counter;
 pub use std::hint::black_box;
 
 #[doc(inline)]
-pub use crate::{alloc::AllocProfiler, bench::Bencher, divan::Divan};
+pub use crate::{
+    alloc::AllocProfiler,
+    bench::{Bencher, Sampling},
+    divan::Divan,
+    report::OutputFormat,
+};
 
 /// Runs all registered benchmarks.
 ///
@@ -274,16 +281,24 @@ pub fn black_box_drop<T>(dummy: T) {
 /// - [`args`]
 /// - [`consts`]
 /// - [`types`]
+/// - [`baseline`]
+/// - [`baseline_type`]
 /// - [`sample_count`]
 /// - [`sample_size`]
+/// - [`sampling`]
 /// - [`threads`]
 /// - [`counters`]
 ///     - [`bytes_count`]
 ///     - [`chars_count`]
 ///     - [`items_count`]
+/// - [`warmup_time`]
+/// - [`warmup_iters`]
 /// - [`min_time`]
 /// - [`max_time`]
+/// - [`max_sample_time`]
 /// - [`skip_ext_time`]
+/// - [`target_precision`]
+/// - [`regression_threshold`]
 /// - [`ignore`]
 ///
 /// ## `name`
@@ -541,6 +556,64 @@ pub fn black_box_drop<T>(dummy: T) {
 /// [`BTreeSet`]: std::collections::BTreeSet
 /// [`HashSet`]: std::collections::HashSet
 ///
+/// ## `baseline`
+/// [`baseline`]: #baseline
+///
+/// A benchmark can be compared against another benchmark function via the
+/// [`baseline`] option. Its output then includes an extra column with how many
+/// times faster or slower it is than the baseline, such as `1.8x faster`.
+///
+/// ```
+/// #[divan::bench]
+/// fn old() {
+///     // ...
+/// }
+///
+/// #[divan::bench(baseline = old)]
+/// fn new() {
+///     // ...
+/// }
+/// ```
+///
+/// The baseline must be a `#[divan::bench]` function accessible as if it were
+/// private, such as one in the same module or referenced via `super::`.
+///
+/// Benchmark cases are matched by their [`types`], [`consts`], and [`args`]
+/// values, as well as by their [`threads`] count. Values that the baseline does
+/// not have are ignored, so a generic benchmark can be compared against a
+/// non-generic one.
+///
+/// The baseline is run even if it is filtered out, using the options of the
+/// benchmark being compared except for options set on the baseline itself.
+///
+/// ## `baseline_type`
+/// [`baseline_type`]: #baseline_type
+///
+/// Instances of a generic benchmark can be compared against one of its
+/// [`types`] via the [`baseline_type`] option. Every other type is then shown
+/// relative to the instance of that type with the same [`consts`] and [`args`]
+/// values.
+///
+/// The following example shows how much faster or slower [`VecDeque`] and
+/// [`LinkedList`] are than [`Vec`]:
+///
+/// ```
+/// use std::collections::{LinkedList, VecDeque};
+///
+/// #[divan::bench(
+///     types = [Vec<i32>, VecDeque<i32>, LinkedList<i32>],
+///     baseline_type = Vec<i32>,
+/// )]
+/// fn from_iter<T: FromIterator<i32>>() -> T {
+///     (0..1000).collect()
+/// }
+/// ```
+///
+/// This option cannot be combined with [`baseline`].
+///
+/// [`LinkedList`]: std::collections::LinkedList
+/// [`VecDeque`]: std::collections::VecDeque
+///
 /// ## `sample_count`
 /// [`sample_count`]: #sample_count
 ///
@@ -578,6 +651,38 @@ pub fn black_box_drop<T>(dummy: T) {
 /// }
 /// ```
 ///
+/// ## `sampling`
+/// [`sampling`]: #sampling
+///
+/// By default, every sample runs the same number of iterations and the time of
+/// each iteration is its sample's time divided by that number. With
+/// `sampling = linear`, sample sizes instead grow linearly (1·k, 2·k, …, n·k)
+/// and the time per iteration is estimated by the slope of a least-squares fit
+/// over samples. This cancels constant per-sample overhead rather than
+/// subtracting a measured estimate of it. The fit's R² is shown alongside the
+/// slope as a measure of its quality.
+///
+/// When the sample size is chosen automatically, `k` is scaled so that linear
+/// sampling runs about as many iterations as flat sampling would. For slow
+/// benchmarks where `k` cannot go below 1, fewer samples are taken instead.
+///
+/// Other time stats, such as the median and mean, are still each sample's time
+/// divided by its size. Because per-sample overhead is not subtracted from
+/// them in this mode, they include it and are slightly higher than the slope.
+///
+/// This may be overridden at runtime using either the `DIVAN_SAMPLING`
+/// environment variable or `--sampling` CLI argument.
+///
+/// ```
+/// #[divan::bench(sampling = linear)]
+/// fn add() -> i32 {
+///     // ...
+///     # 0
+/// }
+/// ```
+///
+/// See [`Sampling`] for more info.
+///
 /// ## `threads`
 /// [`threads`]: #threads
 ///
@@ -691,6 +796,47 @@ pub fn black_box_drop<T>(dummy: T) {
 /// Convenience shorthand for
 /// <code>[counter](#counters) = [ItemsCount](counter::ItemsCount)::from(n)</code>.
 ///
+/// ## `warmup_time`
+/// [`warmup_time`]: #warmup_time
+///
+/// Before tuning [`sample_size`] and collecting samples, each function can be
+/// run without measurement for a predetermined [`Duration`] via the
+/// [`warmup_time`] option. This lets caches, branch predictors, lazily
+/// initialized statics, and CPU frequency settle before anything is recorded.
+/// This may be overridden at runtime using either the `DIVAN_WARMUP_TIME`
+/// environment variable or `--warmup-time` CLI argument.
+///
+/// Warm-up includes time external to the benchmarked function and is not
+/// counted towards [`min_time`] or [`max_time`]. Like [`min_time`], it can
+/// also be set with seconds as [`u64`] or [`f64`].
+///
+/// ```
+/// #[divan::bench(warmup_time = 0.5)]
+/// fn add() -> i32 {
+///     // ...
+///     # 0
+/// }
+/// ```
+///
+/// ## `warmup_iters`
+/// [`warmup_iters`]: #warmup_iters
+///
+/// The minimum number of iterations run without measurement before tuning and
+/// collecting samples can be set to a predetermined [`u32`] value via the
+/// [`warmup_iters`] option. This may be overridden at runtime using either the
+/// `DIVAN_WARMUP_ITERS` environment variable or `--warmup-iters` CLI argument.
+///
+/// If both [`warmup_time`] and [`warmup_iters`] are set, warm-up runs until
+/// both are reached.
+///
+/// ```
+/// #[divan::bench(warmup_iters = 1000)]
+/// fn add() -> i32 {
+///     // ...
+///     # 0
+/// }
+/// ```
+///
 /// ## `min_time`
 /// [`min_time`]: #min_time
 ///
@@ -772,6 +918,32 @@ pub fn black_box_drop<T>(dummy: T) {
 /// }
 /// ```
 ///
+/// ## `max_sample_time`
+/// [`max_sample_time`]: #max_sample_time
+///
+/// When [`sample_size`] is not set, it is chosen automatically by doubling it
+/// until samples are long enough to time precisely. The [`max_sample_time`]
+/// option sets a [`Duration`] budget for the wall time of a single sample,
+/// which stops doubling early. If a collected sample later exceeds it, samples
+/// collected so far are discarded and collection restarts with half the sample
+/// size, so that all samples have the same size. This budget includes time external to the benchmarked function, such as time
+/// spent generating inputs and running [`Drop`]. It is 10 milliseconds by
+/// default, and may be overridden at runtime using either the
+/// `DIVAN_MAX_SAMPLE_TIME` environment variable or `--max-sample-time` CLI
+/// argument.
+///
+/// In the following example, generating each input is much slower than
+/// benchmarking it, so samples are kept to at most 1 millisecond:
+///
+/// ```
+/// #[divan::bench(max_sample_time = 0.001)]
+/// fn clear(bencher: divan::Bencher) {
+///     bencher
+///         .with_inputs(|| "a".repeat(1000))
+///         .bench_refs(|s| s.clear());
+/// }
+/// ```
+///
 /// ## `skip_ext_time`
 /// [`skip_ext_time`]: #skip_ext_time
 ///
@@ -806,6 +978,50 @@ pub fn black_box_drop<T>(dummy: T) {
 /// }
 /// ```
 ///
+/// ## `target_precision`
+/// [`target_precision`]: #target_precision
+///
+/// Rather than choosing a fixed [`sample_count`], the [`target_precision`]
+/// option collects samples until the [confidence
+/// interval](crate::Divan::confidence_level) around the median time is within a
+/// fraction of the median, such as `0.01` for ±1%. Precision is checked each
+/// time the sample count doubles, starting at 10 samples. Stable benchmarks
+/// stop before reaching [`sample_count`] once precise enough, while noisy ones
+/// continue past it, up to 64 times [`sample_count`] and never past
+/// [`max_time`]. This may be overridden at runtime using either the
+/// `DIVAN_TARGET_PRECISION` environment variable or `--target-precision` CLI
+/// argument, which accept percentages like `1%`. Values that are not positive
+/// cause a panic at runtime.
+///
+/// In the following example, sampling continues past 100 samples until the
+/// median is within ±1%, for up to 10 seconds:
+///
+/// ```
+/// #[divan::bench(target_precision = 0.01, max_time = 10)]
+/// fn noisy() {
+///     // ...
+/// }
+/// ```
+///
+/// ## `regression_threshold`
+/// [`regression_threshold`]: #regression_threshold
+///
+/// When comparing against a baseline with `--baseline`, the
+/// `--fail-on-regression` CLI argument makes the benchmark runner exit with an
+/// error if any benchmark became significantly slower by more than a given
+/// fraction. The [`regression_threshold`] option overrides that fraction for a
+/// specific benchmark.
+///
+/// In the following example, `noisy` is only considered a regression if its
+/// median time increased by more than 10%:
+///
+/// ```
+/// #[divan::bench(regression_threshold = 0.1)]
+/// fn noisy() {
+///     // ...
+/// }
+/// ```
+///
 /// ## `ignore`
 /// [`ignore`]: #ignore
 ///
@@ -943,14 +1159,20 @@ pub use divan_macros::bench;
 /// - [`crate`]
 /// - [`sample_count`]
 /// - [`sample_size`]
+/// - [`sampling`]
 /// - [`threads`]
 /// - [`counters`]
 ///     - [`bytes_count`]
 ///     - [`chars_count`]
 ///     - [`items_count`]
+/// - [`warmup_time`]
+/// - [`warmup_iters`]
 /// - [`min_time`]
 /// - [`max_time`]
+/// - [`max_sample_time`]
 /// - [`skip_ext_time`]
+/// - [`target_precision`]
+/// - [`regression_threshold`]
 /// - [`ignore`]
 ///
 /// ## `name`
@@ -1033,6 +1255,11 @@ pub use divan_macros::bench;
 /// }
 /// ```
 ///
+/// ## `sampling`
+/// [`sampling`]: #sampling
+///
+/// See [`#[divan::bench(sampling = ...)]`](macro@bench#sampling).
+///
 /// ## `threads`
 /// [`threads`]: #threads
 ///
@@ -1128,6 +1355,16 @@ pub use divan_macros::bench;
 /// Convenience shorthand for
 /// <code>[counter](#counters) = [ItemsCount](counter::ItemsCount)::from(n)</code>.
 ///
+/// ## `warmup_time`
+/// [`warmup_time`]: #warmup_time
+///
+/// See [`#[divan::bench(warmup_time = ...)]`](macro@bench#warmup_time).
+///
+/// ## `warmup_iters`
+/// [`warmup_iters`]: #warmup_iters
+///
+/// See [`#[divan::bench(warmup_iters = ...)]`](macro@bench#warmup_iters).
+///
 /// ## `min_time`
 /// [`min_time`]: #min_time
 ///
@@ -1209,6 +1446,11 @@ pub use divan_macros::bench;
 /// }
 /// ```
 ///
+/// ## `max_sample_time`
+/// [`max_sample_time`]: #max_sample_time
+///
+/// See [`#[divan::bench(max_sample_time = ...)]`](macro@bench#max_sample_time).
+///
 /// ## `skip_ext_time`
 /// [`skip_ext_time`]: #skip_ext_time
 ///
@@ -1246,6 +1488,30 @@ pub use divan_macros::bench;
 /// }
 /// ```
 ///
+/// ## `target_precision`
+/// [`target_precision`]: #target_precision
+///
+/// See [`#[divan::bench(target_precision = ...)]`](macro@bench#target_precision).
+///
+/// ## `regression_threshold`
+/// [`regression_threshold`]: #regression_threshold
+///
+/// When comparing against a baseline with `--baseline`, the
+/// `--fail-on-regression` CLI argument makes the benchmark runner exit with an
+/// error if any benchmark became significantly slower by more than a given
+/// fraction. The [`regression_threshold`] option overrides that fraction for
+/// all benchmarks in the group.
+///
+/// In the following example, benchmarks in `noisy` are only considered
+/// regressions if their median times increased by more than 10%:
+///
+/// ```
+/// #[divan::bench_group(regression_threshold = 0.1)]
+/// mod noisy {
+///     // ...
+/// }
+/// ```
+///
 /// ## `ignore`
 /// [`ignore`]: #ignore
 ///
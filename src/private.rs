@@ -4,7 +4,7 @@ pub use std::{
 use std::{borrow::Borrow, fmt::Debug};
 
 pub use crate::{
-    bench::{BenchArgs, BenchOptions},
+    bench::{BenchArgs, BenchOptions, Sampling},
     entry::{
         BenchEntry, BenchEntryRunner, EntryConst, EntryList, EntryLocation, EntryMeta, EntryType,
         GenericBenchEntry, GroupEntry, BENCH_ENTRIES, GROUP_ENTRIES,
@@ -165,6 +165,13 @@ pub fn new_counter_set() -> crate::counter::CounterSet {
     Default::default()
 }
 
+/// Used by `#[divan::bench(target_precision = ...)]` to validate the value.
+#[track_caller]
+pub fn target_precision(precision: f64) -> f64 {
+    assert!(precision > 0.0 && precision.is_finite(), "target precision must be positive");
+    precision
+}
+
 /// Used by `#[divan::bench]` to truncate arrays for generic `const` benchmarks.
 pub const fn shrink_array<T, const IN: usize, const OUT: usize>(
     array: [T; IN],
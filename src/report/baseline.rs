@@ -0,0 +1,478 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    report::{json, Node, Reporter},
+    stats::{Comparison, RunTimes, Stats, Verdict},
+    util::{
+        self,
+        json::{JsonValue, JsonWriter},
+    },
+};
+
+/// Returns the file in which the baseline called `name` is stored,
+/// `target/divan/baselines/<name>.json`.
+pub(crate) fn baseline_path(name: &str) -> PathBuf {
+    util::divan_dir().join("baselines").join(format!("{name}.json"))
+}
+
+/// Previously saved benchmark results, keyed by full path.
+pub(crate) struct Baseline {
+    pub name: String,
+
+    /// Benchmarks in the order they were saved.
+    entries: Vec<BaselineEntry>,
+
+    /// Indices into `entries`, keyed by full path.
+    indices: HashMap<String, usize>,
+}
+
+struct BaselineEntry {
+    path: String,
+
+    /// Names of the benchmark and its parents, which may themselves contain
+    /// `::`, such as `Vec<std::string::String>`.
+    segments: Vec<String>,
+
+    stats: Stats,
+
+    /// `stats.samples` in nanoseconds.
+    sample_nanos: Vec<f64>,
+}
+
+impl Baseline {
+    /// Loads the baseline called `name` that was saved by
+    /// [`SaveBaselineReporter`].
+    pub fn load(name: &str) -> io::Result<Self> {
+        let json = std::fs::read_to_string(baseline_path(name))?;
+        Self::parse(name, &json)
+    }
+
+    /// Loads results from a file saved by [`SaveBaselineReporter`] or output
+    /// by [`JsonReporter`](crate::report::JsonReporter), named after the file.
+    pub fn load_file(path: &Path) -> io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let name = path.file_stem().unwrap_or(path.as_os_str()).to_string_lossy();
+        Self::parse(&name, &json)
+    }
+
+    /// Parses results from either a saved baseline, where `benchmarks` maps
+    /// full paths to stats, or from `--format=json` output, where
+    /// `benchmarks` is a tree.
+    pub fn parse(name: &str, json: &str) -> io::Result<Self> {
+        let invalid = |error: &dyn std::fmt::Display| {
+            io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+        };
+
+        let value = JsonValue::parse(json).map_err(|error| invalid(&error))?;
+
+        let mut baseline =
+            Self { name: name.to_owned(), entries: Vec::new(), indices: HashMap::new() };
+
+        let benchmarks = value.get("benchmarks");
+
+        if let Some(benchmarks) = benchmarks.and_then(JsonValue::as_object) {
+            for (path, stats) in benchmarks {
+                // Hand-written results may omit segments, in which case the
+                // path is split on every `::`.
+                let segments = match stats.get("segments").and_then(JsonValue::as_array) {
+                    Some(segments) => {
+                        segments.iter().filter_map(JsonValue::as_str).map(str::to_owned).collect()
+                    }
+                    None => path.split("::").map(str::to_owned).collect(),
+                };
+                baseline.insert(path.clone(), segments, stats);
+            }
+        } else if let Some(tree) = benchmarks.and_then(JsonValue::as_array) {
+            baseline.insert_tree(&mut Vec::new(), tree);
+        } else {
+            return Err(invalid(&"missing \"benchmarks\" object"));
+        }
+
+        Ok(baseline)
+    }
+
+    /// Inserts the leaves of a `--format=json` tree.
+    fn insert_tree(&mut self, segments: &mut Vec<String>, tree: &[JsonValue]) {
+        for node in tree {
+            let Some(name) = node.get("name").and_then(JsonValue::as_str) else {
+                continue;
+            };
+            segments.push(name.to_owned());
+
+            if let Some(children) = node.get("children").and_then(JsonValue::as_array) {
+                self.insert_tree(segments, children);
+            } else if let Some(stats) = node.get("stats") {
+                self.insert(segments.join("::"), segments.clone(), stats);
+            }
+
+            segments.pop();
+        }
+    }
+
+    fn insert(&mut self, path: String, segments: Vec<String>, stats: &JsonValue) {
+        let Some(stats) = json::parse_stats(stats) else {
+            return;
+        };
+
+        let sample_nanos = stats.sample_nanos().collect();
+
+        self.indices.insert(path.clone(), self.entries.len());
+        self.entries.push(BaselineEntry { path, segments, stats, sample_nanos });
+    }
+
+    /// Returns the times of the benchmark at `path`.
+    pub fn get(&self, path: &str) -> Option<RunTimes<'_>> {
+        let entry = &self.entries[*self.indices.get(path)?];
+        let time = entry.stats.time_nanos();
+
+        Some(RunTimes { median: time.median, mean: time.mean, samples: &entry.sample_nanos })
+    }
+
+    /// Returns each benchmark's full path and stats in the order they were
+    /// saved.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Stats)> {
+        self.entries.iter().map(|entry| (entry.path.as_str(), &entry.stats))
+    }
+
+    /// Returns each benchmark's path segments and stats in the order they
+    /// were saved.
+    pub fn iter_segments(&self) -> impl Iterator<Item = (&[String], &Stats)> {
+        self.entries.iter().map(|entry| (entry.segments.as_slice(), &entry.stats))
+    }
+}
+
+/// Benchmark that became slower than its baseline by more than its threshold.
+pub(crate) struct Regression {
+    path: String,
+    median_change: f64,
+    threshold: f64,
+}
+
+/// Checks benchmarks for regressions against a baseline.
+pub(crate) struct RegressionGate<'a> {
+    baseline: &'a Baseline,
+
+    /// The threshold used for benchmarks without `regression_threshold`.
+    default_threshold: f64,
+
+    regressions: Vec<Regression>,
+}
+
+impl<'a> RegressionGate<'a> {
+    pub fn new(baseline: &'a Baseline, default_threshold: f64) -> Self {
+        Self { baseline, default_threshold, regressions: Vec::new() }
+    }
+
+    /// Records the benchmark `node` if it regressed beyond `threshold`.
+    pub fn check(&mut self, node: &Node, stats: &Stats, threshold: Option<f64>) {
+        let Some(old) = self.baseline.get(&node.baseline_key()) else {
+            return;
+        };
+
+        let time = stats.time_nanos();
+        let samples: Vec<f64> = stats.sample_nanos().collect();
+        let new = RunTimes { median: time.median, mean: time.mean, samples: &samples };
+
+        let comparison = Comparison::new(&old, &new);
+        let threshold = threshold.unwrap_or(self.default_threshold);
+
+        if comparison.verdict == Verdict::Slower && comparison.median_change > threshold {
+            self.regressions.push(Regression {
+                path: node.path.to_owned(),
+                median_change: comparison.median_change,
+                threshold,
+            });
+        }
+    }
+
+    /// Prints a summary of regressions and returns `true` if there were any.
+    pub fn report(&self) -> bool {
+        if self.regressions.is_empty() {
+            return false;
+        }
+
+        let count = self.regressions.len();
+        eprintln!(
+            "error: {count} benchmark{} regressed compared to baseline '{}':",
+            if count == 1 { "" } else { "s" },
+            self.baseline.name,
+        );
+
+        for regression in &self.regressions {
+            eprintln!(
+                "  {}: {:+.2}% median (threshold {:.2}%)",
+                regression.path,
+                regression.median_change * 100.0,
+                regression.threshold * 100.0,
+            );
+        }
+
+        true
+    }
+}
+
+/// Saves the statistics and samples of each benchmarked leaf to a named
+/// baseline once all benchmarks have run.
+pub(crate) struct SaveBaselineReporter {
+    name: String,
+
+    /// The file to save to, usually [`baseline_path`].
+    path: PathBuf,
+
+    json: JsonWriter,
+
+    /// Names of the current leaf's parents.
+    parent_names: Vec<String>,
+
+    /// The current leaf.
+    leaf_name: String,
+    leaf_key: String,
+
+    /// The current leaf's thread count, if it is not already its name.
+    leaf_thread_name: Option<String>,
+}
+
+impl SaveBaselineReporter {
+    pub fn new(name: String, path: PathBuf) -> Self {
+        let mut json = JsonWriter::new();
+        json.begin_object();
+        json.key("name").str(&name);
+        json.key("benchmarks").begin_object();
+
+        Self {
+            name,
+            path,
+            json,
+            parent_names: Vec::new(),
+            leaf_name: String::new(),
+            leaf_key: String::new(),
+            leaf_thread_name: None,
+        }
+    }
+}
+
+impl Reporter for SaveBaselineReporter {
+    fn start_parent(&mut self, node: &Node) {
+        self.parent_names.push(node.name.to_owned());
+    }
+
+    fn finish_parent(&mut self) {
+        self.parent_names.pop();
+    }
+
+    fn start_leaf(&mut self, node: &Node) {
+        node.name.clone_into(&mut self.leaf_name);
+        self.leaf_key = node.baseline_key().into_owned();
+        self.leaf_thread_name = node.hidden_thread_name();
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        // Benchmarks are keyed by their full path, which includes `args`,
+        // `types`, `consts`, and `t=N` thread counts.
+        self.json.key(&self.leaf_key).begin_object();
+
+        // Names may contain `::`, so the path cannot be split to get them.
+        self.json.key("segments").begin_array();
+        for name in &self.parent_names {
+            self.json.str(name);
+        }
+        self.json.str(&self.leaf_name);
+        if let Some(thread_name) = &self.leaf_thread_name {
+            self.json.str(thread_name);
+        }
+        self.json.end_array();
+
+        json::write_stats_fields(&mut self.json, stats);
+
+        self.json.key("sample_ns").begin_array();
+        for sample in stats.sample_nanos() {
+            self.json.f64(sample);
+        }
+        self.json.end_array();
+
+        self.json.end_object();
+    }
+
+    fn finish(&mut self) {
+        self.json.end_object().end_object();
+
+        let path = &self.path;
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(path, self.json.as_str()));
+
+        match result {
+            Ok(()) => eprintln!("Baseline '{}' saved to {}", self.name, path.display()),
+            Err(error) => {
+                eprintln!("warning: Failed to save baseline to {}: {error}", path.display())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::report::NodeKind;
+
+    #[test]
+    fn parse() {
+        let json = r#"{"name":"main","benchmarks":{
+            "math::add":{"samples":2,"time":{"median":1.5,"mean":2},"sample_ns":[1,2]},
+            "math::sub":{"samples":0}
+        }}"#;
+
+        let baseline = Baseline::parse("main", json).unwrap();
+
+        let add = baseline.get("math::add").unwrap();
+        assert_eq!((add.median, add.mean, add.samples), (1.5, 2.0, &[1.0, 2.0][..]));
+
+        assert!(baseline.get("math::sub").is_none());
+        assert!(baseline.get("math::mul").is_none());
+
+        assert!(Baseline::parse("main", "{}").is_err());
+    }
+
+    #[test]
+    fn parse_tree() {
+        let json = r#"{"benchmarks":[
+            {"name":"math","kind":"group","children":[
+                {"name":"add","kind":"bench","stats":{"time":{"median":1.5,"mean":2}}},
+                {"name":"sub","kind":"bench","ignored":true}
+            ]}
+        ]}"#;
+
+        let baseline = Baseline::parse("main", json).unwrap();
+
+        let add = baseline.get("math::add").unwrap();
+        assert_eq!((add.median, add.mean, add.samples), (1.5, 2.0, &[][..]));
+
+        assert!(baseline.get("math::sub").is_none());
+        assert_eq!(baseline.iter().map(|(path, _)| path).collect::<Vec<_>>(), ["math::add"]);
+    }
+
+    #[test]
+    fn parse_tree_segments() {
+        let json = r#"{"benchmarks":[
+            {"name":"vec","kind":"group","children":[
+                {"name":"Vec<std::string::String>","kind":"type","stats":{"time":{}}}
+            ]}
+        ]}"#;
+
+        let baseline = Baseline::parse("main", json).unwrap();
+
+        let segments: Vec<&[String]> =
+            baseline.iter_segments().map(|(segments, _)| segments).collect();
+        assert_eq!(segments, [["vec", "Vec<std::string::String>"]]);
+        assert!(baseline.get("vec::Vec<std::string::String>").is_some());
+    }
+
+    #[test]
+    fn save_round_trip() {
+        let path = std::env::temp_dir().join(format!("divan-baseline-{}.json", std::process::id()));
+
+        let stats = Stats { sample_count: 2, iter_count: 4, ..Stats::test(1.5, 2.0) };
+
+        let group = Node {
+            name: "Vec<std::string::String>",
+            path: "vec::Vec<std::string::String>",
+            kind: NodeKind::Type,
+            is_last: true,
+            baseline: None,
+            thread_count: None,
+        };
+        let leaf = Node {
+            name: "8",
+            path: "vec::Vec<std::string::String>::8",
+            kind: NodeKind::Arg,
+            thread_count: Some(NonZeroUsize::MIN),
+            ..group
+        };
+
+        let mut reporter = SaveBaselineReporter::new("main".to_owned(), path.clone());
+        reporter.start_parent(&Node { name: "vec", path: "vec", kind: NodeKind::Group, ..group });
+        reporter.start_parent(&group);
+        reporter.start_leaf(&leaf);
+        reporter.finish_leaf(&stats);
+        reporter.finish_parent();
+        reporter.finish_parent();
+        reporter.finish();
+
+        let baseline = Baseline::load_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // The thread count is saved even though it is not shown.
+        assert!(baseline.get("vec::Vec<std::string::String>::8").is_none());
+        let times = baseline.get("vec::Vec<std::string::String>::8::t=1").unwrap();
+        assert_eq!((times.median, times.mean), (1.5, 2.0));
+
+        let (segments, saved) = baseline.iter_segments().next().unwrap();
+        assert_eq!(segments, ["vec", "Vec<std::string::String>", "8", "t=1"]);
+        assert_eq!((saved.sample_count(), saved.iter_count()), (2, 4));
+    }
+
+    #[test]
+    fn regression_gate() {
+        // Saves benchmarks with 10 samples spread evenly above each median.
+        let parse = |name: &str, medians: &[(&str, f64)]| {
+            let benchmarks: Vec<String> = medians
+                .iter()
+                .map(|&(path, median)| {
+                    let samples: Vec<String> =
+                        (0..10).map(|i| (median + i as f64 * 0.1).to_string()).collect();
+                    format!(
+                        r#""{path}":{{"time":{{"median":{median},"mean":{median}}},"sample_ns":[{}]}}"#,
+                        samples.join(",")
+                    )
+                })
+                .collect();
+
+            Baseline::parse(name, &format!(r#"{{"benchmarks":{{{}}}}}"#, benchmarks.join(",")))
+                .unwrap()
+        };
+
+        let old = parse("old", &[("slower", 100.0), ("much_slower", 100.0), ("faster", 100.0)]);
+        let new = parse(
+            "new",
+            &[("slower", 110.0), ("much_slower", 130.0), ("faster", 50.0), ("added", 999.0)],
+        );
+        let new_stats = |path: &str| new.iter().find(|&(p, _)| p == path).unwrap().1;
+
+        let leaf = |path| Node {
+            name: path,
+            path,
+            kind: NodeKind::Bench,
+            is_last: false,
+            baseline: None,
+            thread_count: None,
+        };
+
+        // Only changes beyond the default threshold are regressions.
+        let mut gate = RegressionGate::new(&old, 0.2);
+        for path in ["slower", "much_slower", "faster", "added"] {
+            gate.check(&leaf(path), new_stats(path), None);
+        }
+        let paths: Vec<&str> = gate.regressions.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["much_slower"]);
+        assert!(gate.report());
+
+        // Per-benchmark thresholds override the default.
+        let mut gate = RegressionGate::new(&old, 0.2);
+        gate.check(&leaf("slower"), new_stats("slower"), Some(0.05));
+        gate.check(&leaf("much_slower"), new_stats("much_slower"), Some(0.5));
+        let paths: Vec<&str> = gate.regressions.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, ["slower"]);
+
+        // Nothing to report without regressions.
+        let mut gate = RegressionGate::new(&old, 0.2);
+        gate.check(&leaf("faster"), new_stats("faster"), None);
+        assert!(!gate.report());
+    }
+}
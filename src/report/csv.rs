@@ -0,0 +1,227 @@
+use std::fmt::Write;
+
+use crate::{
+    alloc::AllocOp,
+    counter::KnownCounterKind,
+    report::{Node, Reporter},
+    stats::{Outliers, Stats},
+    tree_painter::TreeColumn,
+};
+
+/// Writes one CSV row per benchmarked leaf.
+///
+/// Rows are buffered until all benchmarks have finished so that only counter
+/// and allocation columns with values in any row are emitted.
+pub(crate) struct CsvReporter {
+    /// The full path of the current leaf.
+    leaf_path: String,
+
+    rows: Vec<(String, Option<Stats>)>,
+}
+
+impl CsvReporter {
+    pub fn new() -> Self {
+        Self { leaf_path: String::new(), rows: Vec::new() }
+    }
+}
+
+impl Reporter for CsvReporter {
+    fn start_parent(&mut self, _node: &Node) {}
+
+    fn finish_parent(&mut self) {}
+
+    fn ignore_leaf(&mut self, _node: &Node) {}
+
+    fn start_leaf(&mut self, node: &Node) {
+        node.path.clone_into(&mut self.leaf_path);
+    }
+
+    fn finish_empty_leaf(&mut self) {
+        self.rows.push((std::mem::take(&mut self.leaf_path), None));
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        self.rows.push((std::mem::take(&mut self.leaf_path), Some(stats.clone())));
+    }
+
+    fn finish(&mut self) {
+        print!("{}", self.to_csv());
+    }
+}
+
+impl CsvReporter {
+    /// Formats the header and buffered rows.
+    fn to_csv(&self) -> String {
+        let all_stats = || self.rows.iter().filter_map(|(_, stats)| stats.as_ref());
+
+        let counter_kinds: Vec<KnownCounterKind> = KnownCounterKind::ALL
+            .into_iter()
+            .filter(|&kind| all_stats().any(|stats| stats.get_counts(kind).is_some()))
+            .collect();
+
+        let alloc_ops: Vec<AllocOp> = AllocOp::ALL
+            .into_iter()
+            .filter(|&op| all_stats().any(|stats| !stats.alloc_tallies.get(op).is_zero()))
+            .collect();
+
+        let mut buf = String::new();
+
+        // Write header.
+        buf.push_str("path");
+        for column in TreeColumn::ALL {
+            if column.is_time_stat() {
+                _ = write!(buf, ",{}_ns", column.name());
+            } else if column.is_ci() {
+                _ = write!(buf, ",{0}_lower_ns,{0}_upper_ns", column.name());
+            } else if column == TreeColumn::Outliers {
+                buf.push_str(",low_severe_outliers,low_mild_outliers");
+                buf.push_str(",high_mild_outliers,high_severe_outliers");
+            } else {
+                _ = write!(buf, ",{}", column.name());
+            }
+        }
+        for kind in &counter_kinds {
+            for column in TreeColumn::time_stats() {
+                _ = write!(buf, ",{}_per_sec_{}", kind.name(), column.name());
+            }
+        }
+        for op in &alloc_ops {
+            for tally in ["count", "size"] {
+                for column in TreeColumn::time_stats() {
+                    _ = write!(buf, ",{}_{tally}_{}", op.name(), column.name());
+                }
+            }
+        }
+        let field_count = buf.matches(',').count();
+        buf.push('\n');
+
+        for (path, stats) in &self.rows {
+            write_field(&mut buf, path);
+
+            // Leaves without statistics have empty fields after their path.
+            let Some(stats) = stats else {
+                buf.push_str(&",".repeat(field_count));
+                buf.push('\n');
+                continue;
+            };
+
+            for column in TreeColumn::ALL {
+                buf.push(',');
+                match column {
+                    TreeColumn::Samples => _ = write!(buf, "{}", stats.sample_count),
+                    TreeColumn::Iters => _ = write!(buf, "{}", stats.iter_count),
+                    TreeColumn::MedianCi | TreeColumn::MeanCi => {
+                        if let Some(ci) = column.get_ci(stats) {
+                            write_f64(&mut buf, ci.lower.as_nanos_f64());
+                            buf.push(',');
+                            write_f64(&mut buf, ci.upper.as_nanos_f64());
+                        } else {
+                            buf.push(',');
+                        }
+                    }
+                    TreeColumn::RSquared => {
+                        if let Some(value) = column.get_value(stats) {
+                            write_f64(&mut buf, value);
+                        }
+                    }
+                    TreeColumn::Outliers => {
+                        let Outliers { low_severe, low_mild, high_mild, high_severe, .. } =
+                            stats.outliers;
+                        _ = write!(buf, "{low_severe},{low_mild},{high_mild},{high_severe}");
+                    }
+                    _ => {
+                        if let Some(time) = column.get_time(stats) {
+                            write_f64(&mut buf, time.as_nanos_f64());
+                        }
+                    }
+                }
+            }
+
+            for &kind in &counter_kinds {
+                let throughput = stats.get_throughput(kind);
+
+                for column in TreeColumn::time_stats() {
+                    buf.push(',');
+                    if let Some(&value) = throughput.as_ref().and_then(|t| column.get_stat(t)) {
+                        write_f64(&mut buf, value);
+                    }
+                }
+            }
+
+            for &op in &alloc_ops {
+                let tally = stats.alloc_tallies.get(op);
+
+                for stats in tally.as_array() {
+                    for column in TreeColumn::time_stats() {
+                        buf.push(',');
+                        if let Some(&value) = column.get_stat(stats) {
+                            write_f64(&mut buf, value);
+                        }
+                    }
+                }
+            }
+
+            buf.push('\n');
+        }
+
+        buf
+    }
+}
+
+/// Writes a field, quoting it if it contains special characters.
+///
+/// Paths commonly contain commas, such as with `HashMap<K, V>`.
+fn write_field(buf: &mut String, field: &str) {
+    if field.contains([',', '"', '\n', '\r']) {
+        buf.push('"');
+        buf.push_str(&field.replace('"', "\"\""));
+        buf.push('"');
+    } else {
+        buf.push_str(field);
+    }
+}
+
+/// Writes a number, leaving the field empty if not finite.
+fn write_f64(buf: &mut String, value: f64) {
+    if value.is_finite() {
+        _ = write!(buf, "{value}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_leaf_fields() {
+        let stats = Stats { sample_count: 2, iter_count: 2, ..Stats::test(1.5, 2.0) };
+
+        let mut reporter = CsvReporter::new();
+        reporter.rows.push(("math::add".to_owned(), Some(stats)));
+        reporter.rows.push(("math::sub".to_owned(), None));
+
+        let csv = reporter.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+
+        // Every row has as many fields as the header.
+        for line in &lines {
+            assert_eq!(line.matches(',').count(), lines[0].matches(',').count(), "{line}");
+        }
+        assert!(lines[2].strip_prefix("math::sub").unwrap().chars().all(|c| c == ','));
+    }
+
+    #[test]
+    fn write_field() {
+        #[track_caller]
+        fn test(field: &str, expected: &str) {
+            let mut buf = String::new();
+            super::write_field(&mut buf, field);
+            assert_eq!(buf, expected);
+        }
+
+        test("a::b", "a::b");
+        test("HashMap<u64, u64>::10", "\"HashMap<u64, u64>::10\"");
+        test("say \"hi\", bye", "\"say \"\"hi\"\", bye\"");
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    report::{json, Baseline, Node, Reporter},
+    stats::Stats,
+    util::{self, json::JsonWriter},
+};
+
+/// Previous runs read from a history file written by [`HistoryReporter`].
+pub(crate) struct History {
+    /// Runs in the order they were appended.
+    runs: Vec<Baseline>,
+
+    /// The number of malformed lines that were skipped, such as from an
+    /// interrupted run.
+    pub skipped_lines: usize,
+}
+
+impl History {
+    /// Loads the runs on the last `count` lines of the JSON-lines file at
+    /// `path`.
+    ///
+    /// A missing file is treated as having no runs, since it is created by
+    /// the first run that appends to it. Malformed lines are skipped and
+    /// counted in `skipped_lines`.
+    pub fn load(path: &std::path::Path, count: usize) -> io::Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+
+        let mut runs = Vec::new();
+        let mut skipped_lines = 0;
+
+        // Read from the end to only parse the last `count` lines.
+        let lines = contents.lines().rev().filter(|line| !line.trim().is_empty()).take(count);
+        for line in lines {
+            match Baseline::parse("", line) {
+                Ok(run) => runs.push(run),
+                Err(_) => skipped_lines += 1,
+            }
+        }
+
+        runs.reverse();
+        Ok(Self { runs, skipped_lines })
+    }
+
+    /// Returns the median times in nanoseconds of the benchmark at `path`,
+    /// from oldest to newest, skipping runs that did not include it.
+    pub fn medians<'a>(&'a self, path: &'a str) -> impl Iterator<Item = f64> + 'a {
+        self.runs.iter().filter_map(move |run| run.get(path)).map(|times| times.median)
+    }
+}
+
+/// Draws `values` as a sparkline of block characters, one per value.
+pub(crate) fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+
+    values
+        .iter()
+        .map(|&value| {
+            // Flat lines are drawn in the middle to not look like improvements.
+            if range <= 0.0 {
+                return BARS[BARS.len() / 2 - 1];
+            }
+
+            let index = ((value - min) / range * (BARS.len() - 1) as f64).round() as usize;
+            BARS[index.min(BARS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Appends the statistics of each benchmarked leaf to a JSON-lines history file
+/// once all benchmarks have run.
+///
+/// Each line is an object with a Unix `timestamp` in seconds, the `git_head`
+/// commit if available, an optional `label`, and `benchmarks` keyed by full
+/// path like saved baselines, but without samples.
+pub(crate) struct HistoryReporter {
+    path: PathBuf,
+
+    json: JsonWriter,
+
+    /// The full path of the current leaf.
+    leaf_path: String,
+}
+
+impl HistoryReporter {
+    pub fn new(path: PathBuf, label: Option<&str>) -> Self {
+        let timestamp =
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+
+        let mut json = JsonWriter::new();
+        json.begin_object();
+        json.key("timestamp").uint(timestamp);
+
+        if let Some(commit) = util::git::head_commit() {
+            json.key("git_head").str(&commit);
+        }
+
+        if let Some(label) = label {
+            json.key("label").str(label);
+        }
+
+        json.key("benchmarks").begin_object();
+
+        Self { path, json, leaf_path: String::new() }
+    }
+}
+
+impl Reporter for HistoryReporter {
+    fn start_leaf(&mut self, node: &Node) {
+        node.path.clone_into(&mut self.leaf_path);
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        self.json.key(&self.leaf_path).begin_object();
+        json::write_stats_fields(&mut self.json, stats);
+        self.json.end_object();
+    }
+
+    fn finish(&mut self) {
+        self.json.end_object().end_object();
+
+        let path = &self.path;
+        let result = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::OpenOptions::new().create(true).append(true).open(path))
+            .and_then(|mut file| writeln!(file, "{}", self.json.as_str()));
+
+        if let Err(error) = result {
+            eprintln!("warning: Failed to append history to {}: {error}", path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparkline() {
+        assert_eq!(super::sparkline(&[]), "");
+        assert_eq!(super::sparkline(&[5.0]), "▄");
+        assert_eq!(super::sparkline(&[2.0, 2.0]), "▄▄");
+        assert_eq!(super::sparkline(&[1.0, 8.0, 4.5]), "▁█▅");
+    }
+
+    #[test]
+    fn load() {
+        let path = std::env::temp_dir().join(format!("divan-history-{}.jsonl", std::process::id()));
+
+        let missing = History::load(&path, 10).unwrap();
+        assert_eq!(missing.medians("a").count(), 0);
+
+        let lines = [
+            r#"{"timestamp":1,"benchmarks":{"a":{"time":{"median":1}}}}"#,
+            r#"{"timestamp":2,"benchmarks":{"a":{"time":{"median":2}},"b":{"time":{"median":5}}}}"#,
+            r#"{"timestamp":3,"label":"x","benchmarks":{"a":{"time":{"median":3}}}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let history = History::load(&path, 2).unwrap();
+        assert_eq!(history.medians("a").collect::<Vec<_>>(), [2.0, 3.0]);
+        assert_eq!(history.medians("b").collect::<Vec<_>>(), [5.0]);
+        assert_eq!(history.skipped_lines, 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn load_malformed() {
+        let path = std::env::temp_dir()
+            .join(format!("divan-history-malformed-{}.jsonl", std::process::id()));
+
+        // The second line was cut short, such as by an interrupted run.
+        let lines = [
+            r#"{"timestamp":1,"benchmarks":{"a":{"time":{"median":1}}}}"#,
+            r#"{"timestamp":2,"benchmarks":{"a":{"time":{"med"#,
+            r#"{"timestamp":3,"benchmarks":{"a":{"time":{"median":3}}}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        // Malformed lines count towards the lines examined.
+        let history = History::load(&path, 2).unwrap();
+        assert_eq!(history.medians("a").collect::<Vec<_>>(), [3.0]);
+        assert_eq!(history.skipped_lines, 1);
+
+        let history = History::load(&path, 3).unwrap();
+        assert_eq!(history.medians("a").collect::<Vec<_>>(), [1.0, 3.0]);
+        assert_eq!(history.skipped_lines, 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,403 @@
+use std::{fmt::Write, path::PathBuf};
+
+use crate::{
+    alloc::AllocOp,
+    counter::{AnyCounter, BytesFormat, KnownCounterKind},
+    report::{
+        tree::{ReportNode, TreeBuilder},
+        Node, NodeKind, Reporter,
+    },
+    stats::Stats,
+    time::FineDuration,
+    tree_painter::{ColumnSet, TreeColumn},
+    util,
+};
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+details { margin-left: 1.25em; }
+body > details { margin-left: 0; }
+summary { cursor: pointer; font-weight: 600; padding: 0.2em 0; }
+table { border-collapse: collapse; margin: 0.3em 0 0.6em 1.25em; }
+th, td { padding: 0.2em 0.8em; text-align: left; vertical-align: top; }
+th { cursor: pointer; user-select: none; border-bottom: 1px solid #aaa; }
+th[data-order=asc]::after { content: \" \\25B2\"; }
+th[data-order=desc]::after { content: \" \\25BC\"; }
+tbody tr:nth-child(odd) { background: #f4f4f4; }
+td { font-variant-numeric: tabular-nums; white-space: nowrap; }
+.sub { color: #666; font-size: 0.85em; }
+.ignored { color: #999; font-style: italic; }
+svg polyline { fill: none; stroke: #3572a5; stroke-width: 1.5; }
+";
+
+const SCRIPT: &str = "\
+document.querySelectorAll('th[data-col]').forEach(function (th) {
+  th.addEventListener('click', function () {
+    var table = th.closest('table'), body = table.tBodies[0], col = +th.dataset.col;
+    var asc = th.dataset.order !== 'asc';
+    table.querySelectorAll('th').forEach(function (h) { delete h.dataset.order; });
+    th.dataset.order = asc ? 'asc' : 'desc';
+    var rows = Array.from(body.rows);
+    rows.sort(function (a, b) {
+      var x = a.cells[col].dataset.sort, y = b.cells[col].dataset.sort;
+      var nx = parseFloat(x), ny = parseFloat(y);
+      var c = isNaN(nx) || isNaN(ny) ? x.localeCompare(y, undefined, { numeric: true }) : nx - ny;
+      return asc ? c : -c;
+    });
+    rows.forEach(function (row) { body.appendChild(row); });
+  });
+});
+";
+
+const CHART_WIDTH: f64 = 160.0;
+const CHART_HEIGHT: f64 = 32.0;
+
+/// Writes a self-contained HTML page to a file once all benchmarks have run.
+pub(crate) struct HtmlReporter {
+    path: PathBuf,
+    bytes_format: BytesFormat,
+    columns: ColumnSet,
+    tree: TreeBuilder,
+}
+
+impl HtmlReporter {
+    pub fn new(path: PathBuf, bytes_format: BytesFormat, columns: ColumnSet) -> Self {
+        Self { path, bytes_format, columns, tree: TreeBuilder::default() }
+    }
+
+    fn write_nodes(&self, buf: &mut String, nodes: &[ReportNode]) {
+        // Consecutive leaves share a table so that nodes keep their
+        // registration order.
+        let mut leaves: Vec<&ReportNode> = Vec::new();
+
+        for node in nodes {
+            if node.children.is_empty() {
+                leaves.push(node);
+                continue;
+            }
+
+            if !leaves.is_empty() {
+                self.write_table(buf, &leaves);
+                leaves.clear();
+            }
+
+            let parent = node;
+            buf.push_str("<details open><summary title=\"");
+            write_escaped(buf, &parent.path);
+            buf.push_str("\">");
+            write_name(buf, parent);
+            buf.push_str("</summary>\n");
+
+            self.write_nodes(buf, &parent.children);
+
+            buf.push_str("</details>\n");
+        }
+
+        if !leaves.is_empty() {
+            self.write_table(buf, &leaves);
+        }
+    }
+
+    fn write_table(&self, buf: &mut String, leaves: &[&ReportNode]) {
+        buf.push_str("<table>\n<thead><tr><th data-col=\"0\">name</th>");
+        for (i, column) in self.columns.columns.iter().enumerate() {
+            _ = write!(buf, "<th data-col=\"{}\">{}</th>", i + 1, column.name());
+        }
+        buf.push_str("<th>samples chart</th></tr></thead>\n<tbody>\n");
+
+        // Cells after the name, including the chart.
+        let cell_count = self.columns.columns.len() + 1;
+
+        for leaf in leaves {
+            buf.push_str("<tr><td data-sort=\"");
+            write_escaped(buf, &leaf.name);
+            buf.push_str("\" title=\"");
+            write_escaped(buf, &leaf.path);
+            buf.push_str("\">");
+            write_name(buf, leaf);
+            buf.push_str("</td>");
+
+            if leaf.ignored {
+                buf.push_str("<td data-sort=\"\" class=\"ignored\">(ignored)</td>");
+                for _ in 1..cell_count {
+                    buf.push_str("<td data-sort=\"\"></td>");
+                }
+            } else if let Some(stats) = &leaf.stats {
+                for &column in &self.columns.columns {
+                    self.write_cell(buf, column, stats);
+                }
+
+                buf.push_str("<td>");
+                write_chart(buf, &stats.samples);
+                buf.push_str("</td>");
+            } else {
+                for _ in &self.columns.columns {
+                    buf.push_str("<td data-sort=\"\"></td>");
+                }
+                buf.push_str("<td></td>");
+            }
+
+            buf.push_str("</tr>\n");
+        }
+
+        buf.push_str("</tbody>\n</table>\n");
+    }
+
+    fn write_cell(&self, buf: &mut String, column: TreeColumn, stats: &Stats) {
+        if let Some(text) = column.get_text(stats) {
+            buf.push_str("<td data-sort=\"");
+            if let Some(value) = column.get_value(stats) {
+                _ = write!(buf, "{value}");
+            }
+            buf.push_str("\">");
+            write_escaped(buf, &text);
+            buf.push_str("</td>");
+            return;
+        }
+
+        let Some(time) = column.get_time(stats) else {
+            buf.push_str("<td data-sort=\"\"></td>");
+            return;
+        };
+
+        _ = write!(buf, "<td data-sort=\"{}\">{time}", time.picos);
+
+        for counter_kind in KnownCounterKind::ALL {
+            let Some(&count) = stats
+                .get_counts(counter_kind)
+                .filter(|_| self.columns.show_throughput)
+                .and_then(|c| column.get_stat(c))
+            else {
+                continue;
+            };
+
+            let throughput = AnyCounter::known(counter_kind, count)
+                .display_throughput(time, self.bytes_format)
+                .to_string();
+
+            buf.push_str("<div class=\"sub\">");
+            write_escaped(buf, &throughput);
+            buf.push_str("</div>");
+        }
+
+        for op in [AllocOp::Alloc, AllocOp::Dealloc, AllocOp::Grow, AllocOp::Shrink] {
+            let tally = stats.alloc_tallies.get(op);
+            if !self.columns.show_alloc || tally.is_zero() {
+                continue;
+            }
+
+            let (Some(&count), Some(&size)) =
+                (column.get_stat(&tally.count), column.get_stat(&tally.size))
+            else {
+                continue;
+            };
+
+            _ = write!(
+                buf,
+                "<div class=\"sub\">{} {} ({})</div>",
+                op.prefix(),
+                util::fmt::format_f64(count, 4),
+                util::fmt::format_bytes(size, 4, self.bytes_format),
+            );
+        }
+
+        buf.push_str("</td>");
+    }
+}
+
+impl Reporter for HtmlReporter {
+    fn start_parent(&mut self, node: &Node) {
+        self.tree.start_parent(node);
+    }
+
+    fn finish_parent(&mut self) {
+        self.tree.finish_parent();
+    }
+
+    fn ignore_leaf(&mut self, node: &Node) {
+        self.tree.ignore_leaf(node);
+    }
+
+    fn start_leaf(&mut self, node: &Node) {
+        self.tree.start_leaf(node);
+    }
+
+    fn finish_empty_leaf(&mut self) {
+        self.tree.finish_empty_leaf();
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        self.tree.finish_leaf(stats);
+    }
+
+    fn finish(&mut self) {
+        let roots = self.tree.take_roots();
+
+        let mut buf = String::new();
+        buf.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        buf.push_str("<title>Benchmark Report</title>\n<style>\n");
+        buf.push_str(STYLE);
+        buf.push_str("</style>\n</head>\n<body>\n<h1>Benchmark Report</h1>\n");
+
+        self.write_nodes(&mut buf, &roots);
+
+        buf.push_str("<script>\n");
+        buf.push_str(SCRIPT);
+        buf.push_str("</script>\n</body>\n</html>\n");
+
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&self.path, buf));
+
+        match result {
+            Ok(()) => eprintln!("HTML report written to {}", self.path.display()),
+            Err(error) => {
+                eprintln!(
+                    "warning: Failed to write HTML report to {}: {error}",
+                    self.path.display()
+                )
+            }
+        }
+    }
+}
+
+/// Writes an SVG line chart of per-iteration sample durations in the order they
+/// were taken.
+fn write_chart(buf: &mut String, samples: &[FineDuration]) {
+    let Some(max) = samples.iter().max() else {
+        return;
+    };
+    let min = samples.iter().min().unwrap_or(max);
+
+    let range = (max.picos - min.picos) as f64;
+    let x_step = if samples.len() > 1 { CHART_WIDTH / (samples.len() - 1) as f64 } else { 0.0 };
+
+    _ = write!(
+        buf,
+        "<svg width=\"{CHART_WIDTH}\" height=\"{CHART_HEIGHT}\"><title>{} samples from {min} to {max}</title><polyline points=\"",
+        samples.len(),
+    );
+
+    for (i, sample) in samples.iter().enumerate() {
+        let ratio = if range == 0.0 { 0.5 } else { (sample.picos - min.picos) as f64 / range };
+
+        // Leave room for the stroke at the edges.
+        let y = (CHART_HEIGHT - 2.0) * (1.0 - ratio) + 1.0;
+
+        _ = write!(buf, "{:.1},{y:.1} ", i as f64 * x_step);
+    }
+
+    // Draw a flat line for a single sample.
+    if samples.len() == 1 {
+        _ = write!(buf, "{CHART_WIDTH},{:.1}", CHART_HEIGHT / 2.0);
+    }
+
+    buf.push_str("\"/></svg>");
+}
+
+/// Writes the node's name, formatting generic and runtime values as code.
+fn write_name(buf: &mut String, node: &ReportNode) {
+    let is_code = matches!(node.kind, NodeKind::Type | NodeKind::Const | NodeKind::Arg);
+
+    if is_code {
+        buf.push_str("<code>");
+    }
+    write_escaped(buf, &node.name);
+    if is_code {
+        buf.push_str("</code>");
+    }
+}
+
+fn write_escaped(buf: &mut String, s: &str) {
+    for ch in s.chars() {
+        match ch {
+            '&' => buf.push_str("&amp;"),
+            '<' => buf.push_str("&lt;"),
+            '>' => buf.push_str("&gt;"),
+            '"' => buf.push_str("&quot;"),
+            ch => buf.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Column;
+
+    #[test]
+    fn escape() {
+        let mut buf = String::new();
+        write_escaped(&mut buf, "HashMap<&str, \"u64\">");
+        assert_eq!(buf, "HashMap&lt;&amp;str, &quot;u64&quot;&gt;");
+    }
+
+    fn node(name: &str, kind: NodeKind, children: Vec<ReportNode>) -> ReportNode {
+        ReportNode {
+            name: name.to_owned(),
+            path: name.to_owned(),
+            kind,
+            children,
+            stats: None,
+            ignored: false,
+        }
+    }
+
+    #[test]
+    fn registration_order() {
+        let reporter =
+            HtmlReporter::new(PathBuf::new(), BytesFormat::default(), ColumnSet::default());
+
+        let group = node("group", NodeKind::Group, vec![node("inner", NodeKind::Bench, vec![])]);
+        let nodes =
+            [node("first", NodeKind::Bench, vec![]), group, node("last", NodeKind::Bench, vec![])];
+
+        let mut buf = String::new();
+        reporter.write_nodes(&mut buf, &nodes);
+
+        let first = buf.find(">first<").unwrap();
+        let group = buf.find(">group<").unwrap();
+        let last = buf.find(">last<").unwrap();
+        assert!(first < group && group < last);
+    }
+
+    #[test]
+    fn ignored_width() {
+        for columns in [vec![], TreeColumn::DEFAULT.to_vec()] {
+            let header_width = columns.len() + 2;
+            let reporter = HtmlReporter::new(
+                PathBuf::new(),
+                BytesFormat::default(),
+                ColumnSet { columns, ..ColumnSet::empty() },
+            );
+
+            let mut ignored = node("ignored", NodeKind::Bench, vec![]);
+            ignored.ignored = true;
+
+            let mut buf = String::new();
+            reporter.write_table(&mut buf, &[&ignored]);
+
+            assert_eq!(buf.matches("</th>").count(), header_width);
+            assert_eq!(buf.matches("</td>").count(), header_width);
+        }
+    }
+
+    #[test]
+    fn throughput() {
+        let stats = &Stats { sample_count: 100, ..Stats::test(1.0, 1.0) }
+            .with_count(KnownCounterKind::Items, 1000);
+
+        let cell = |columns: &[Column]| {
+            let reporter =
+                HtmlReporter::new(PathBuf::new(), BytesFormat::default(), ColumnSet::new(columns));
+            let mut buf = String::new();
+            reporter.write_cell(&mut buf, TreeColumn::Median, stats);
+            buf
+        };
+
+        assert!(!cell(&[Column::Median]).contains("item/s"));
+        assert!(cell(&[Column::Median, Column::Throughput]).contains("1 Titem/s"));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{
+    alloc::{AllocOp, AllocOpMap, AllocTally},
+    counter::{KnownCounterKind, MaxCountUInt},
+    report::{Node, NodeKind, Reporter},
+    stats::{ConfidenceInterval, Distribution, Outliers, Regression, Stats, StatsSet},
+    time::FineDuration,
+    util::json::{JsonValue, JsonWriter},
+};
+
+/// Writes the entry tree and statistics as a single JSON document.
+///
+/// The document is only printed once all benchmarks have finished, so that the
+/// output is never partially valid.
+pub(crate) struct JsonReporter {
+    json: JsonWriter,
+}
+
+impl JsonReporter {
+    pub fn new() -> Self {
+        let mut json = JsonWriter::new();
+        json.begin_object().key("benchmarks").begin_array();
+        Self { json }
+    }
+
+    fn write_node(&mut self, node: &Node) {
+        self.json.begin_object();
+        self.json.key("name").str(node.name);
+        self.json.key("kind").str(node.kind.name());
+
+        if let NodeKind::Threads(count) = node.kind {
+            self.json.key("threads").uint(count.get() as u64);
+        }
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn start_parent(&mut self, node: &Node) {
+        self.write_node(node);
+        self.json.key("children").begin_array();
+    }
+
+    fn finish_parent(&mut self) {
+        self.json.end_array().end_object();
+    }
+
+    fn ignore_leaf(&mut self, node: &Node) {
+        self.write_node(node);
+        self.json.key("ignored").bool(true).end_object();
+    }
+
+    fn start_leaf(&mut self, node: &Node) {
+        self.write_node(node);
+    }
+
+    fn finish_empty_leaf(&mut self) {
+        self.json.end_object();
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        self.json.key("stats");
+        write_stats(&mut self.json, stats);
+        self.json.end_object();
+    }
+
+    fn finish(&mut self) {
+        self.json.end_array().end_object();
+        println!("{}", self.json.as_str());
+    }
+}
+
+/// Writes `stats` as a JSON object.
+///
+/// Times are in nanoseconds and throughputs are per second.
+pub(crate) fn write_stats(json: &mut JsonWriter, stats: &Stats) {
+    json.begin_object();
+    write_stats_fields(json, stats);
+    json.end_object();
+}
+
+/// Writes the fields of [`write_stats`] within the current object.
+pub(crate) fn write_stats_fields(json: &mut JsonWriter, stats: &Stats) {
+    json.key("samples").uint(stats.sample_count);
+    json.key("iters").uint(stats.iter_count);
+
+    let outliers = stats.outliers;
+    json.key("outliers").begin_object();
+    json.key("low_severe").uint(outliers.low_severe);
+    json.key("low_mild").uint(outliers.low_mild);
+    json.key("high_mild").uint(outliers.high_mild);
+    json.key("high_severe").uint(outliers.high_severe);
+    json.end_object();
+
+    // Percentiles and dispersion are alongside the `StatsSet` values.
+    let time = stats.time_nanos();
+    let distribution = stats.time_distribution_nanos();
+    json.key("time").begin_object();
+    json.key("fastest").f64(time.fastest);
+    json.key("slowest").f64(time.slowest);
+    json.key("median").f64(time.median);
+    json.key("mean").f64(time.mean);
+    json.key("p5").f64(distribution.p5);
+    json.key("p25").f64(distribution.p25);
+    json.key("p75").f64(distribution.p75);
+    json.key("p95").f64(distribution.p95);
+    json.key("p99").f64(distribution.p99);
+    json.key("std_dev").f64(distribution.std_dev);
+    json.key("mad").f64(distribution.mad);
+    if let Some(regression) = stats.regression {
+        json.key("slope").f64(regression.slope);
+        json.key("r_squared").f64(regression.r_squared);
+    }
+    for (key, ci) in [("median_ci", stats.median_ci_nanos()), ("mean_ci", stats.mean_ci_nanos())] {
+        let Some(ci) = ci else {
+            continue;
+        };
+
+        json.key(key).begin_object();
+        json.key("lower").f64(ci.lower);
+        json.key("upper").f64(ci.upper);
+        json.key("level").f64(ci.level);
+        json.end_object();
+    }
+    json.end_object();
+
+    json.key("counters").begin_object();
+    for counter_kind in KnownCounterKind::ALL {
+        let (Some(counts), Some(throughput)) =
+            (stats.get_counts(counter_kind), stats.get_throughput(counter_kind))
+        else {
+            continue;
+        };
+
+        json.key(counter_kind.name()).begin_object();
+        json.key("count");
+        write_stats_set(json, &counts.map(|&count| count as f64));
+        json.key("throughput");
+        write_stats_set(json, &throughput);
+        json.end_object();
+    }
+    json.end_object();
+
+    json.key("alloc").begin_object();
+    for op in AllocOp::ALL {
+        let tally = stats.alloc_tallies.get(op);
+        if tally.is_zero() {
+            continue;
+        }
+
+        json.key(op.name()).begin_object();
+        json.key("count");
+        write_stats_set(json, &tally.count);
+        json.key("size");
+        write_stats_set(json, &tally.size);
+        json.end_object();
+    }
+    json.end_object();
+}
+
+/// Parses stats written by [`write_stats_fields`], including samples if
+/// present as `sample_ns`.
+///
+/// Only `time` is required, and other missing values are zero.
+pub(crate) fn parse_stats(value: &JsonValue) -> Option<Stats> {
+    let number = |value: Option<&JsonValue>| value.and_then(JsonValue::as_f64).unwrap_or_default();
+
+    let stats_set = |set: Option<&JsonValue>| StatsSet {
+        fastest: number(set.and_then(|set| set.get("fastest"))),
+        slowest: number(set.and_then(|set| set.get("slowest"))),
+        median: number(set.and_then(|set| set.get("median"))),
+        mean: number(set.and_then(|set| set.get("mean"))),
+    };
+
+    let confidence_interval = |ci: Option<&JsonValue>| {
+        let ci = ci?;
+        Some(ConfidenceInterval {
+            lower: FineDuration::from_nanos_f64(number(ci.get("lower"))),
+            upper: FineDuration::from_nanos_f64(number(ci.get("upper"))),
+            level: number(ci.get("level")),
+        })
+    };
+
+    let time = value.get("time")?;
+    let counters = value.get("counters");
+    let alloc = value.get("alloc");
+
+    Some(Stats {
+        sample_count: number(value.get("samples")) as u32,
+        iter_count: number(value.get("iters")) as u64,
+
+        time: stats_set(Some(time)).map(|&nanos| FineDuration::from_nanos_f64(nanos)),
+
+        time_distribution: Distribution {
+            p5: number(time.get("p5")),
+            p25: number(time.get("p25")),
+            p75: number(time.get("p75")),
+            p95: number(time.get("p95")),
+            p99: number(time.get("p99")),
+            std_dev: number(time.get("std_dev")),
+            mad: number(time.get("mad")),
+        }
+        .map(|&nanos| FineDuration::from_nanos_f64(nanos)),
+
+        outliers: {
+            let outliers = value.get("outliers");
+            let count = |key| number(outliers.and_then(|outliers| outliers.get(key))) as u32;
+            Outliers {
+                low_severe: count("low_severe"),
+                low_mild: count("low_mild"),
+                high_mild: count("high_mild"),
+                high_severe: count("high_severe"),
+            }
+        },
+
+        regression: time
+            .get("slope")
+            .and_then(JsonValue::as_f64)
+            .map(|slope| Regression { slope, r_squared: number(time.get("r_squared")) }),
+
+        median_ci: confidence_interval(time.get("median_ci")),
+        mean_ci: confidence_interval(time.get("mean_ci")),
+
+        alloc_tallies: AllocOpMap {
+            values: AllocOp::ALL.map(|op| {
+                let tally = alloc.and_then(|alloc| alloc.get(op.name()));
+                AllocTally {
+                    count: stats_set(tally.and_then(|tally| tally.get("count"))),
+                    size: stats_set(tally.and_then(|tally| tally.get("size"))),
+                }
+            }),
+        },
+
+        counts: KnownCounterKind::ALL.map(|counter_kind| {
+            let count = counters?.get(counter_kind.name())?.get("count");
+            Some(stats_set(count).map(|&count| count as MaxCountUInt))
+        }),
+
+        samples: value
+            .get("sample_ns")
+            .and_then(JsonValue::as_array)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(JsonValue::as_f64)
+            .map(FineDuration::from_nanos_f64)
+            .collect(),
+    })
+}
+
+fn write_stats_set(json: &mut JsonWriter, set: &StatsSet<f64>) {
+    json.begin_object();
+    json.key("fastest").f64(set.fastest);
+    json.key("slowest").f64(set.slowest);
+    json.key("median").f64(set.median);
+    json.key("mean").f64(set.mean);
+    json.end_object();
+}
@@ -0,0 +1,195 @@
+use std::io::{self, Write};
+
+use crate::{
+    config::Action,
+    report::{json::write_stats, Node, NodeKind, Reporter},
+    stats::Stats,
+    util::json::JsonWriter,
+};
+
+/// Writes a JSON object on its own line for each event as it happens.
+///
+/// Each line is flushed immediately so that consumers can show progress during
+/// long runs.
+pub(crate) struct JsonLinesReporter<W = io::Stdout> {
+    action: Action,
+
+    out: W,
+
+    json: JsonWriter,
+
+    /// The full path of the current leaf.
+    leaf_path: String,
+
+    finished_count: u64,
+    ignored_count: u64,
+}
+
+impl JsonLinesReporter {
+    pub fn new(action: Action) -> Self {
+        Self::with_output(action, io::stdout())
+    }
+}
+
+impl<W: Write> JsonLinesReporter<W> {
+    fn with_output(action: Action, out: W) -> Self {
+        Self {
+            action,
+            out,
+            json: JsonWriter::new(),
+            leaf_path: String::new(),
+            finished_count: 0,
+            ignored_count: 0,
+        }
+    }
+
+    /// Starts a new event object.
+    fn begin_event(&mut self, event: &str) -> &mut JsonWriter {
+        self.json.clear();
+        self.json.begin_object().key("event").str(event)
+    }
+
+    /// Starts a new event object describing `node`.
+    fn begin_node_event(&mut self, event: &str, node: &Node) -> &mut JsonWriter {
+        let json = self.begin_event(event);
+        json.key("name").str(node.name);
+        json.key("path").str(node.path);
+        json.key("kind").str(node.kind.name());
+
+        if let NodeKind::Threads(count) = node.kind {
+            json.key("threads").uint(count.get() as u64);
+        }
+
+        json
+    }
+
+    /// Ends the current event object and writes it as a line.
+    fn end_event(&mut self) {
+        self.json.end_object();
+
+        _ = writeln!(self.out, "{}", self.json.as_str());
+        _ = self.out.flush();
+    }
+}
+
+impl<W: Write> Reporter for JsonLinesReporter<W> {
+    fn start(&mut self) {
+        let action = match self.action {
+            Action::Bench => "bench",
+            Action::Test => "test",
+            Action::List => "list",
+        };
+
+        self.begin_event("suite_started").key("action").str(action);
+        self.end_event();
+    }
+
+    fn start_parent(&mut self, node: &Node) {
+        self.begin_node_event("group_started", node);
+        self.end_event();
+    }
+
+    fn finish_parent(&mut self) {}
+
+    fn ignore_leaf(&mut self, node: &Node) {
+        self.ignored_count += 1;
+
+        self.begin_node_event("bench_ignored", node);
+        self.end_event();
+    }
+
+    fn start_leaf(&mut self, node: &Node) {
+        node.path.clone_into(&mut self.leaf_path);
+
+        self.begin_node_event("bench_started", node);
+        self.end_event();
+    }
+
+    fn finish_empty_leaf(&mut self) {
+        self.finished_count += 1;
+
+        let path = std::mem::take(&mut self.leaf_path);
+        self.begin_event("bench_finished").key("path").str(&path);
+        self.end_event();
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        self.finished_count += 1;
+
+        let path = std::mem::take(&mut self.leaf_path);
+        let json = self.begin_event("bench_finished");
+        json.key("path").str(&path);
+        json.key("stats");
+        write_stats(json, stats);
+        self.end_event();
+    }
+
+    fn finish(&mut self) {
+        let (finished, ignored) = (self.finished_count, self.ignored_count);
+
+        let json = self.begin_event("suite_finished");
+        json.key("finished").uint(finished);
+        json.key("ignored").uint(ignored);
+        self.end_event();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::json::JsonValue;
+
+    #[test]
+    fn events() {
+        let mut reporter = JsonLinesReporter::with_output(Action::List, Vec::new());
+
+        let group = Node {
+            name: "math",
+            path: "math",
+            kind: NodeKind::Group,
+            is_last: true,
+            baseline: None,
+            thread_count: None,
+        };
+        let add =
+            Node { name: "add", path: "math::add", kind: NodeKind::Bench, is_last: false, ..group };
+        let sub =
+            Node { name: "sub", path: "math::sub", kind: NodeKind::Bench, is_last: true, ..group };
+
+        reporter.start();
+        reporter.start_parent(&group);
+        reporter.start_leaf(&add);
+        reporter.finish_empty_leaf();
+        reporter.ignore_leaf(&sub);
+        reporter.finish_parent();
+        reporter.finish();
+
+        let output = String::from_utf8(reporter.out).unwrap();
+        let events: Vec<JsonValue> =
+            output.lines().map(|line| JsonValue::parse(line).unwrap()).collect();
+
+        let str_field = |i: usize, key: &str| events[i].get(key).and_then(JsonValue::as_str);
+        let count_field = |i: usize, key: &str| events[i].get(key).and_then(JsonValue::as_f64);
+
+        let names: Vec<&str> = (0..events.len()).filter_map(|i| str_field(i, "event")).collect();
+        assert_eq!(
+            names,
+            [
+                "suite_started",
+                "group_started",
+                "bench_started",
+                "bench_finished",
+                "bench_ignored",
+                "suite_finished"
+            ]
+        );
+
+        assert_eq!(str_field(0, "action"), Some("list"));
+        assert_eq!(str_field(1, "path"), Some("math"));
+        assert_eq!(str_field(2, "path"), Some("math::add"));
+        assert_eq!(str_field(3, "path"), Some("math::add"));
+        assert_eq!(str_field(4, "path"), Some("math::sub"));
+        assert_eq!(count_field(5, "finished"), Some(1.0));
+        assert_eq!(count_field(5, "ignored"), Some(1.0));
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt::Write;
+
+use crate::{
+    alloc::AllocOp,
+    counter::{AnyCounter, BytesFormat, KnownCounterKind},
+    report::{Node, Reporter},
+    stats::Stats,
+    tree_painter::ColumnSet,
+    util,
+};
+
+/// Writes a GitHub-flavored Markdown table for each top-level group.
+///
+/// Each table is printed once its group finishes.
+pub(crate) struct MarkdownReporter {
+    bytes_format: BytesFormat,
+
+    columns: ColumnSet,
+
+    depth: usize,
+
+    /// The path prefix stripped from row names, i.e. the top-level group.
+    table_path: String,
+
+    /// The full path of the current leaf.
+    leaf_path: String,
+
+    /// Rows of the current table.
+    rows: String,
+}
+
+impl MarkdownReporter {
+    pub fn new(bytes_format: BytesFormat, columns: ColumnSet) -> Self {
+        Self {
+            bytes_format,
+            columns,
+            depth: 0,
+            table_path: String::new(),
+            leaf_path: String::new(),
+            rows: String::new(),
+        }
+    }
+
+    /// Starts a row with the node's path relative to the current table.
+    fn start_row(&mut self, path: &str) {
+        let name = path
+            .strip_prefix(self.table_path.as_str())
+            .and_then(|name| name.strip_prefix("::"))
+            .unwrap_or(path);
+
+        self.rows.push_str("| `");
+        self.rows.push_str(&name.replace('|', "\\|"));
+        self.rows.push_str("` |");
+    }
+
+    fn flush_table(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+
+        let mut buf = String::new();
+
+        if !self.table_path.is_empty() {
+            _ = writeln!(buf, "## `{}`\n", self.table_path.replace('`', ""));
+        }
+
+        buf.push_str("| benchmark |");
+        for column in &self.columns.columns {
+            _ = write!(buf, " {} |", column.name());
+        }
+        buf.push_str("\n|:---|");
+        for _ in &self.columns.columns {
+            buf.push_str("---:|");
+        }
+        buf.push('\n');
+
+        buf.push_str(&self.rows);
+        self.rows.clear();
+
+        println!("{buf}");
+    }
+}
+
+impl Reporter for MarkdownReporter {
+    fn start_parent(&mut self, node: &Node) {
+        if self.depth == 0 {
+            node.path.clone_into(&mut self.table_path);
+        }
+        self.depth += 1;
+    }
+
+    fn finish_parent(&mut self) {
+        self.depth -= 1;
+        if self.depth == 0 {
+            self.flush_table();
+            self.table_path.clear();
+        }
+    }
+
+    fn ignore_leaf(&mut self, node: &Node) {
+        self.start_row(node.path);
+        self.rows.push_str(" (ignored) |");
+        for _ in 1..self.columns.columns.len() {
+            self.rows.push_str("  |");
+        }
+        self.rows.push('\n');
+    }
+
+    fn start_leaf(&mut self, node: &Node) {
+        node.path.clone_into(&mut self.leaf_path);
+    }
+
+    fn finish_empty_leaf(&mut self) {
+        let path = std::mem::take(&mut self.leaf_path);
+        self.start_row(&path);
+        for _ in &self.columns.columns {
+            self.rows.push_str("  |");
+        }
+        self.rows.push('\n');
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        let path = std::mem::take(&mut self.leaf_path);
+        self.start_row(&path);
+
+        for &column in &self.columns.columns {
+            if let Some(text) = column.get_text(stats) {
+                _ = write!(self.rows, " {text} |");
+                continue;
+            }
+
+            let Some(time) = column.get_time(stats) else {
+                self.rows.push_str("  |");
+                continue;
+            };
+
+            _ = write!(self.rows, " {time}");
+
+            // Counter and allocation stats are placed on separate lines within
+            // the cell, like in the tree output.
+            for counter_kind in KnownCounterKind::ALL {
+                let Some(&count) = stats
+                    .get_counts(counter_kind)
+                    .filter(|_| self.columns.show_throughput)
+                    .and_then(|c| column.get_stat(c))
+                else {
+                    continue;
+                };
+
+                let counter = AnyCounter::known(counter_kind, count);
+                let throughput = counter.display_throughput(time, self.bytes_format);
+                _ = write!(self.rows, "<br>{throughput}");
+            }
+
+            for op in [AllocOp::Alloc, AllocOp::Dealloc, AllocOp::Grow, AllocOp::Shrink] {
+                let tally = stats.alloc_tallies.get(op);
+                if !self.columns.show_alloc || tally.is_zero() {
+                    continue;
+                }
+
+                if let (Some(&count), Some(&size)) =
+                    (column.get_stat(&tally.count), column.get_stat(&tally.size))
+                {
+                    _ = write!(
+                        self.rows,
+                        "<br>{} {} ({})",
+                        op.prefix(),
+                        util::fmt::format_f64(count, 4),
+                        util::fmt::format_bytes(size, 4, self.bytes_format),
+                    );
+                }
+            }
+
+            self.rows.push_str(" |");
+        }
+
+        self.rows.push('\n');
+    }
+
+    fn finish(&mut self) {
+        // Flush leaves that were not within a top-level group.
+        self.flush_table();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        report::{Column, NodeKind},
+        tree_painter::TreeColumn,
+    };
+
+    #[test]
+    fn escape() {
+        let mut reporter = MarkdownReporter::new(BytesFormat::default(), ColumnSet::default());
+        "ops".clone_into(&mut reporter.table_path);
+
+        // Pipes would otherwise end the cell.
+        reporter.start_row("ops::a|b");
+        assert_eq!(reporter.rows, r"| `a\|b` |");
+    }
+
+    #[test]
+    fn ignored_row() {
+        let columns = ColumnSet { columns: TreeColumn::DEFAULT.to_vec(), ..ColumnSet::empty() };
+        let mut reporter = MarkdownReporter::new(BytesFormat::default(), columns);
+
+        let node = Node {
+            name: "sub",
+            path: "math::sub",
+            kind: NodeKind::Bench,
+            is_last: true,
+            baseline: None,
+            thread_count: None,
+        };
+        reporter.start_parent(&Node { name: "math", path: "math", kind: NodeKind::Group, ..node });
+        reporter.ignore_leaf(&node);
+
+        // Each row has a cell for the name and each column.
+        assert_eq!(reporter.rows.matches('|').count(), TreeColumn::DEFAULT.len() + 2);
+        assert!(reporter.rows.starts_with("| `sub` | (ignored) |"));
+    }
+
+    #[test]
+    fn throughput() {
+        let stats = &Stats { sample_count: 100, ..Stats::test(1.0, 1.0) }
+            .with_count(KnownCounterKind::Items, 1000);
+
+        let row = |columns: &[Column]| {
+            let mut reporter =
+                MarkdownReporter::new(BytesFormat::default(), ColumnSet::new(columns));
+            "add".clone_into(&mut reporter.leaf_path);
+            reporter.finish_leaf(stats);
+            reporter.rows
+        };
+
+        assert_eq!(row(&[Column::Median]), "| `add` | 1 ns |\n");
+        assert_eq!(row(&[Column::Median, Column::Throughput]), "| `add` | 1 ns<br>1 Titem/s |\n");
+    }
+}
@@ -0,0 +1,575 @@
+//! Custom reporting of benchmark results.
+//!
+//! Results can be sent somewhere other than the terminal, such as a metrics
+//! store, by implementing [`Reporter`] and registering it with
+//! [`Divan::reporter`](crate::Divan::reporter).
+//!
+//! Alternatively, [`Divan::run_benches_collect`](crate::Divan::run_benches_collect)
+//! returns a [`Report`] of all results once benchmarks have finished.
+//!
+//! # Examples
+//!
+//! The following example prints the median time of each benchmark along with
+//! its full path:
+//!
+//! ```
+//! use divan::report::{Node, Reporter, Stats};
+//!
+//! #[derive(Default)]
+//! struct MedianReporter {
+//!     path: String,
+//! }
+//!
+//! impl Reporter for MedianReporter {
+//!     fn start_leaf(&mut self, node: &Node) {
+//!         self.path = node.path().to_owned();
+//!     }
+//!
+//!     fn finish_leaf(&mut self, stats: &Stats) {
+//!         println!("{}: {} ns", self.path, stats.time_nanos().median);
+//!     }
+//! }
+//!
+//! fn main() {
+//!     divan::Divan::from_args()
+//!         .reporter(MedianReporter::default())
+//!         .main();
+//! }
+//! ```
+
+use std::{borrow::Cow, num::NonZeroUsize};
+
+#[doc(inline)]
+pub use crate::{
+    alloc::AllocOp,
+    stats::{ConfidenceInterval, Distribution, Outliers, Regression, Stats, StatsSet},
+};
+
+pub use self::tree::{Report, ReportNode};
+
+mod baseline;
+mod csv;
+mod history;
+mod html;
+mod json;
+mod json_lines;
+mod markdown;
+mod terse;
+mod tree;
+
+pub(crate) use self::{
+    baseline::{baseline_path, Baseline, RegressionGate, SaveBaselineReporter},
+    csv::CsvReporter,
+    history::{sparkline, History, HistoryReporter},
+    html::HtmlReporter,
+    json::JsonReporter,
+    json_lines::JsonLinesReporter,
+    markdown::MarkdownReporter,
+    terse::TerseReporter,
+    tree::TreeBuilder,
+};
+
+/// The format in which benchmark results are output.
+///
+/// See [`Divan::format`](crate::Divan::format) for more info.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputFormat {
+    /// Human-readable tree with box-drawing characters. This is the default.
+    #[default]
+    Pretty,
+
+    /// One line per benchmark with its full path.
+    Terse,
+
+    /// A single JSON document containing the entry tree and statistics.
+    Json,
+
+    /// One JSON object per line for each event as it happens.
+    JsonLines,
+
+    /// Comma-separated values with one row per benchmark.
+    Csv,
+
+    /// GitHub-flavored Markdown with a table per top-level group.
+    Markdown,
+}
+
+/// Private `OutputFormat` that prevents leaking trait implementations we don't
+/// want to publicly commit to.
+#[derive(Clone, Copy)]
+pub(crate) struct PrivOutputFormat(pub OutputFormat);
+
+impl clap::ValueEnum for PrivOutputFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Self(OutputFormat::Pretty),
+            Self(OutputFormat::Terse),
+            Self(OutputFormat::Json),
+            Self(OutputFormat::JsonLines),
+            Self(OutputFormat::Csv),
+            Self(OutputFormat::Markdown),
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let name = match self.0 {
+            OutputFormat::Pretty => "pretty",
+            OutputFormat::Terse => "terse",
+            OutputFormat::Json => "json",
+            OutputFormat::JsonLines => "json-lines",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Markdown => "markdown",
+        };
+        Some(clap::builder::PossibleValue::new(name))
+    }
+}
+
+/// A column of human-readable output.
+///
+/// See [`Divan::columns`](crate::Divan::columns) for more info.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Column {
+    /// Fastest iteration time.
+    Fastest,
+
+    /// Slowest iteration time.
+    Slowest,
+
+    /// Median iteration time.
+    Median,
+
+    /// Mean iteration time.
+    Mean,
+
+    /// Number of samples taken.
+    Samples,
+
+    /// Number of iterations across all samples.
+    Iters,
+
+    /// 5th percentile of iteration times.
+    P5,
+
+    /// 25th percentile of iteration times.
+    P25,
+
+    /// 75th percentile of iteration times.
+    P75,
+
+    /// 95th percentile of iteration times.
+    P95,
+
+    /// 99th percentile of iteration times.
+    P99,
+
+    /// Standard deviation of iteration times.
+    StdDev,
+
+    /// Median absolute deviation of iteration times.
+    Mad,
+
+    /// Margin of the median's confidence interval, shown as `± 1.23%`.
+    MedianCi,
+
+    /// Margin of the mean's confidence interval, shown as `± 1.23%`.
+    MeanCi,
+
+    /// Number of outlier samples, including how many are severe.
+    Outliers,
+
+    /// Iteration time estimated by [`Sampling::Linear`](crate::Sampling::Linear).
+    Slope,
+
+    /// Goodness of fit of [`Slope`](Self::Slope), from 0 to 1.
+    RSquared,
+
+    /// Allocation counts and sizes, shown in rows below each benchmark's time
+    /// stats rather than as a column of its own.
+    Alloc,
+
+    /// Throughput of each [counter](crate::counter), shown in rows below each
+    /// benchmark's time stats rather than as a column of its own.
+    Throughput,
+}
+
+/// Private `Column` that prevents leaking trait implementations we don't want
+/// to publicly commit to.
+#[derive(Clone, Copy)]
+pub(crate) struct PrivColumn(pub Column);
+
+impl clap::ValueEnum for PrivColumn {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Self(Column::Fastest),
+            Self(Column::Slowest),
+            Self(Column::Median),
+            Self(Column::Mean),
+            Self(Column::Samples),
+            Self(Column::Iters),
+            Self(Column::P5),
+            Self(Column::P25),
+            Self(Column::P75),
+            Self(Column::P95),
+            Self(Column::P99),
+            Self(Column::StdDev),
+            Self(Column::Mad),
+            Self(Column::MedianCi),
+            Self(Column::MeanCi),
+            Self(Column::Outliers),
+            Self(Column::Slope),
+            Self(Column::RSquared),
+            Self(Column::Alloc),
+            Self(Column::Throughput),
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        let name = match self.0 {
+            Column::Fastest => "fastest",
+            Column::Slowest => "slowest",
+            Column::Median => "median",
+            Column::Mean => "mean",
+            Column::Samples => "samples",
+            Column::Iters => "iters",
+            Column::P5 => "p5",
+            Column::P25 => "p25",
+            Column::P75 => "p75",
+            Column::P95 => "p95",
+            Column::P99 => "p99",
+            Column::StdDev => "std_dev",
+            Column::Mad => "mad",
+            Column::MedianCi => "median_ci",
+            Column::MeanCi => "mean_ci",
+            Column::Outliers => "outliers",
+            Column::Slope => "slope",
+            Column::RSquared => "r2",
+            Column::Alloc => "alloc",
+            Column::Throughput => "throughput",
+        };
+        Some(clap::builder::PossibleValue::new(name))
+    }
+}
+
+/// Receives benchmark events in the order in which the entry tree is run.
+///
+/// Every [`start_parent`](Self::start_parent) is paired with a
+/// [`finish_parent`](Self::finish_parent), and every
+/// [`start_leaf`](Self::start_leaf) is paired with either
+/// [`finish_empty_leaf`](Self::finish_empty_leaf) or
+/// [`finish_leaf`](Self::finish_leaf).
+///
+/// All methods do nothing by default, so implementations only need to handle
+/// the events they care about.
+///
+/// See the [module documentation](self) for an example.
+pub trait Reporter {
+    /// Called once before any entries are run.
+    fn start(&mut self) {}
+
+    /// Enter a parent node, such as a module, `#[divan::bench_group]`, or a
+    /// benchmark with multiple `types`, `consts`, `args`, or `threads`.
+    fn start_parent(&mut self, node: &Node) {
+        _ = node;
+    }
+
+    /// Exit the current parent node.
+    fn finish_parent(&mut self) {}
+
+    /// Indicate that the next child node was ignored.
+    ///
+    /// This semantically combines start/finish operations.
+    fn ignore_leaf(&mut self, node: &Node) {
+        _ = node;
+    }
+
+    /// Enter a leaf node.
+    fn start_leaf(&mut self, node: &Node) {
+        _ = node;
+    }
+
+    /// Exit the current leaf node without statistics.
+    ///
+    /// This happens when listing or testing benchmarks rather than running
+    /// them.
+    fn finish_empty_leaf(&mut self) {}
+
+    /// Exit the current leaf node, emitting statistics.
+    fn finish_leaf(&mut self, stats: &Stats) {
+        _ = stats;
+    }
+
+    /// Called once after all entries have been run.
+    fn finish(&mut self) {}
+}
+
+/// Forwards events to multiple reporters in order.
+pub(crate) struct MultiReporter<'a>(pub Vec<&'a mut dyn Reporter>);
+
+impl Reporter for MultiReporter<'_> {
+    fn start(&mut self) {
+        self.0.iter_mut().for_each(|reporter| reporter.start());
+    }
+
+    fn start_parent(&mut self, node: &Node) {
+        self.0.iter_mut().for_each(|reporter| reporter.start_parent(node));
+    }
+
+    fn finish_parent(&mut self) {
+        self.0.iter_mut().for_each(|reporter| reporter.finish_parent());
+    }
+
+    fn ignore_leaf(&mut self, node: &Node) {
+        self.0.iter_mut().for_each(|reporter| reporter.ignore_leaf(node));
+    }
+
+    fn start_leaf(&mut self, node: &Node) {
+        self.0.iter_mut().for_each(|reporter| reporter.start_leaf(node));
+    }
+
+    fn finish_empty_leaf(&mut self) {
+        self.0.iter_mut().for_each(|reporter| reporter.finish_empty_leaf());
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        self.0.iter_mut().for_each(|reporter| reporter.finish_leaf(stats));
+    }
+
+    fn finish(&mut self) {
+        self.0.iter_mut().for_each(|reporter| reporter.finish());
+    }
+}
+
+/// A node in the entry tree being reported.
+#[derive(Clone, Copy, Debug)]
+pub struct Node<'a> {
+    /// The name displayed for this node.
+    pub(crate) name: &'a str,
+
+    /// The names of this node and its ancestors joined by `::`, as used for
+    /// filtering.
+    pub(crate) path: &'a str,
+
+    /// What this node represents.
+    pub(crate) kind: NodeKind,
+
+    /// Whether this is the last child of its parent.
+    pub(crate) is_last: bool,
+
+    /// Stats of the leaf's `baseline` benchmark, if it has one.
+    pub(crate) baseline: Option<&'a Stats>,
+
+    /// The number of threads that a leaf is benchmarked with, if known.
+    pub(crate) thread_count: Option<NonZeroUsize>,
+}
+
+impl<'a> Node<'a> {
+    /// Returns the name displayed for this node.
+    #[inline]
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Returns the names of this node and its ancestors joined by `::`, such
+    /// as `collections::vec::push::1000`.
+    ///
+    /// This is what CLI filters and [`Divan::skip_exact`](crate::Divan::skip_exact)
+    /// match against.
+    #[inline]
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    /// Returns what this node represents.
+    #[inline]
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Returns the stats of the benchmark this leaf is compared against via
+    /// [`#[divan::bench(baseline = ...)]`](macro@crate::bench#baseline).
+    ///
+    /// This is only provided to [`Reporter::start_leaf`] when benchmarking.
+    #[inline]
+    pub fn baseline(&self) -> Option<&'a Stats> {
+        self.baseline
+    }
+
+    /// Returns the `t=N` name of this leaf's thread count if it is not already
+    /// in the path, which is the case when only one thread count is used.
+    pub(crate) fn hidden_thread_name(&self) -> Option<String> {
+        match (self.kind, self.thread_count) {
+            (NodeKind::Threads(_), _) | (_, None) => None,
+            (_, Some(thread_count)) => Some(format!("t={thread_count}")),
+        }
+    }
+
+    /// Returns the key of this leaf in saved baselines.
+    ///
+    /// This always ends with the thread count, so that runs with different
+    /// `threads` are not compared against each other.
+    pub(crate) fn baseline_key(&self) -> Cow<'a, str> {
+        match self.hidden_thread_name() {
+            Some(thread_name) => Cow::Owned(join_path(self.path, &thread_name)),
+            None => Cow::Borrowed(self.path),
+        }
+    }
+}
+
+/// Appends `name` to `parent_path` as a new path component.
+pub(crate) fn join_path(parent_path: &str, name: &str) -> String {
+    if parent_path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{parent_path}::{name}")
+    }
+}
+
+/// What a reported node represents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NodeKind {
+    /// Module or `#[divan::bench_group]`.
+    Group,
+
+    /// `#[divan::bench]` function.
+    Bench,
+
+    /// Generic `types` instance of a benchmark.
+    Type,
+
+    /// Generic `consts` instance of a benchmark.
+    Const,
+
+    /// Runtime `args` value of a benchmark.
+    Arg,
+
+    /// Benchmark run with a specific number of `threads`.
+    Threads(NonZeroUsize),
+}
+
+impl NodeKind {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Group => "group",
+            Self::Bench => "bench",
+            Self::Type => "type",
+            Self::Const => "const",
+            Self::Arg => "arg",
+            Self::Threads(_) => "threads",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the events it receives.
+    #[derive(Default)]
+    struct EventLog(Vec<String>);
+
+    impl Reporter for EventLog {
+        fn start(&mut self) {
+            self.0.push("start".to_owned());
+        }
+
+        fn start_parent(&mut self, node: &Node) {
+            self.0.push(format!("start_parent {}", node.path()));
+        }
+
+        fn finish_parent(&mut self) {
+            self.0.push("finish_parent".to_owned());
+        }
+
+        fn ignore_leaf(&mut self, node: &Node) {
+            self.0.push(format!("ignore_leaf {}", node.path()));
+        }
+
+        fn start_leaf(&mut self, node: &Node) {
+            self.0.push(format!("start_leaf {}", node.path()));
+        }
+
+        fn finish_empty_leaf(&mut self) {
+            self.0.push("finish_empty_leaf".to_owned());
+        }
+
+        fn finish_leaf(&mut self, stats: &Stats) {
+            self.0.push(format!("finish_leaf {}", stats.time_nanos().median));
+        }
+
+        fn finish(&mut self) {
+            self.0.push("finish".to_owned());
+        }
+    }
+
+    #[test]
+    fn multi_reporter() {
+        let stats = Stats::test(2.0, 2.0);
+
+        let group = Node {
+            name: "math",
+            path: "math",
+            kind: NodeKind::Group,
+            is_last: true,
+            baseline: None,
+            thread_count: None,
+        };
+        let node =
+            |name, path, is_last| Node { name, path, kind: NodeKind::Bench, is_last, ..group };
+
+        let mut first = EventLog::default();
+        let mut second = EventLog::default();
+        let mut reporter = MultiReporter(vec![&mut first, &mut second]);
+
+        reporter.start();
+        reporter.start_parent(&group);
+        reporter.start_leaf(&node("add", "math::add", false));
+        reporter.finish_leaf(&stats);
+        reporter.start_leaf(&node("mul", "math::mul", false));
+        reporter.finish_empty_leaf();
+        reporter.ignore_leaf(&node("sub", "math::sub", true));
+        reporter.finish_parent();
+        reporter.finish();
+
+        let expected = [
+            "start",
+            "start_parent math",
+            "start_leaf math::add",
+            "finish_leaf 2",
+            "start_leaf math::mul",
+            "finish_empty_leaf",
+            "ignore_leaf math::sub",
+            "finish_parent",
+            "finish",
+        ];
+        assert_eq!(first.0, expected);
+        assert_eq!(second.0, expected);
+    }
+
+    #[test]
+    fn baseline_key() {
+        let four = NonZeroUsize::new(4);
+        let leaf = Node {
+            name: "add",
+            path: "math::add",
+            kind: NodeKind::Bench,
+            is_last: true,
+            baseline: None,
+            thread_count: four,
+        };
+        assert_eq!(leaf.baseline_key(), "math::add::t=4");
+
+        let thread_leaf = Node {
+            name: "t=4",
+            path: "math::add::t=4",
+            kind: NodeKind::Threads(four.unwrap()),
+            ..leaf
+        };
+        assert_eq!(thread_leaf.baseline_key(), "math::add::t=4");
+
+        // Thread counts are unknown when not benchmarking.
+        assert_eq!(Node { thread_count: None, ..leaf }.baseline_key(), "math::add");
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    config::{Action, RunIgnored},
+    report::{Node, Reporter},
+    stats::Stats,
+};
+
+/// Prints one line per benchmark, starting with its full path.
+///
+/// When listing, this uses the libtest format of `path: benchmark`, so that
+/// tools like IDEs and `cargo-nextest` can discover benchmarks.
+pub(crate) struct TerseReporter {
+    action: Action,
+
+    run_ignored: RunIgnored,
+
+    /// The full path of the current leaf.
+    leaf_path: String,
+}
+
+impl TerseReporter {
+    pub fn new(action: Action, run_ignored: RunIgnored) -> Self {
+        Self { action, run_ignored, leaf_path: String::new() }
+    }
+
+    /// Formats the line of a leaf that will not run, or returns [`None`] if it
+    /// is not shown.
+    fn ignored_line(&self, path: &str) -> Option<String> {
+        // With `--ignored`, leaves that are not run are the ones without
+        // `#[ignore]`, which libtest omits entirely.
+        if !self.run_ignored.run_non_ignored() {
+            return None;
+        }
+
+        // Like libtest, ignored benchmarks are otherwise listed the same as
+        // others.
+        let status = if self.action.is_list() { "benchmark" } else { "ignored" };
+        Some(format!("{path}: {status}"))
+    }
+
+    fn empty_line(&self) -> String {
+        let status = if self.action.is_list() { "benchmark" } else { "ok" };
+        format!("{}: {status}", self.leaf_path)
+    }
+
+    fn stats_line(&self, stats: &Stats) -> String {
+        format!("{}: median {}, mean {}", self.leaf_path, stats.time.median, stats.time.mean)
+    }
+}
+
+impl Reporter for TerseReporter {
+    fn start_parent(&mut self, _node: &Node) {}
+
+    fn finish_parent(&mut self) {}
+
+    fn ignore_leaf(&mut self, node: &Node) {
+        if let Some(line) = self.ignored_line(node.path) {
+            println!("{line}");
+        }
+    }
+
+    fn start_leaf(&mut self, node: &Node) {
+        node.path.clone_into(&mut self.leaf_path);
+    }
+
+    fn finish_empty_leaf(&mut self) {
+        println!("{}", self.empty_line());
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        println!("{}", self.stats_line(stats));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list() {
+        let mut reporter = TerseReporter::new(Action::List, RunIgnored::No);
+        "math::add".clone_into(&mut reporter.leaf_path);
+
+        assert_eq!(reporter.empty_line(), "math::add: benchmark");
+        assert_eq!(reporter.ignored_line("math::sub").unwrap(), "math::sub: benchmark");
+    }
+
+    #[test]
+    fn list_only_ignored() {
+        // Leaves that are not run with `--ignored` lack `#[ignore]`.
+        let reporter = TerseReporter::new(Action::List, RunIgnored::Only);
+        assert_eq!(reporter.ignored_line("math::add"), None);
+
+        let reporter = TerseReporter::new(Action::Bench, RunIgnored::Only);
+        assert_eq!(reporter.ignored_line("math::add"), None);
+    }
+
+    #[test]
+    fn bench() {
+        let mut reporter = TerseReporter::new(Action::Bench, RunIgnored::No);
+        "math::add".clone_into(&mut reporter.leaf_path);
+
+        assert_eq!(reporter.empty_line(), "math::add: ok");
+        assert_eq!(reporter.ignored_line("math::sub").unwrap(), "math::sub: ignored");
+
+        let stats = Stats { sample_count: 2, iter_count: 2, ..Stats::test(1.5, 2.0) };
+        assert_eq!(reporter.stats_line(&stats), "math::add: median 1.5 ns, mean 2 ns");
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{
+    report::{Node, NodeKind, Reporter},
+    stats::Stats,
+};
+
+/// Collected results of running benchmarks.
+///
+/// This is returned by
+/// [`Divan::run_benches_collect`](crate::Divan::run_benches_collect).
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    roots: Vec<ReportNode>,
+}
+
+impl Report {
+    /// Returns the top-level nodes.
+    #[inline]
+    pub fn roots(&self) -> &[ReportNode] {
+        &self.roots
+    }
+
+    /// Returns the node at `path`, such as `collections::vec::push::1000`.
+    pub fn get(&self, path: &str) -> Option<&ReportNode> {
+        let mut nodes = self.roots.as_slice();
+
+        loop {
+            let node = nodes.iter().find(|node| {
+                path.strip_prefix(node.path.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })?;
+
+            if node.path.len() == path.len() {
+                return Some(node);
+            }
+            nodes = &node.children;
+        }
+    }
+
+    /// Returns an iterator over all leaf nodes in the order they were run.
+    pub fn leaves(&self) -> impl Iterator<Item = &ReportNode> {
+        let mut stack: Vec<&ReportNode> = self.roots.iter().rev().collect();
+
+        std::iter::from_fn(move || loop {
+            let node = stack.pop()?;
+            if node.children.is_empty() {
+                return Some(node);
+            }
+            stack.extend(node.children.iter().rev());
+        })
+    }
+}
+
+/// Owned node of a [`Report`].
+#[derive(Clone, Debug)]
+pub struct ReportNode {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) kind: NodeKind,
+    pub(crate) children: Vec<ReportNode>,
+    pub(crate) stats: Option<Stats>,
+    pub(crate) ignored: bool,
+}
+
+impl ReportNode {
+    fn new(node: &Node) -> Self {
+        Self {
+            name: node.name.to_owned(),
+            path: node.path.to_owned(),
+            kind: node.kind,
+            children: Vec::new(),
+            stats: None,
+            ignored: false,
+        }
+    }
+
+    /// Returns the name displayed for this node.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the names of this node and its ancestors joined by `::`.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns what this node represents.
+    #[inline]
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Returns the child nodes, which is empty for leaves.
+    #[inline]
+    pub fn children(&self) -> &[ReportNode] {
+        &self.children
+    }
+
+    /// Returns the statistics of a benchmarked leaf.
+    #[inline]
+    pub fn stats(&self) -> Option<&Stats> {
+        self.stats.as_ref()
+    }
+
+    /// Returns `true` if this leaf was ignored.
+    #[inline]
+    pub fn is_ignored(&self) -> bool {
+        self.ignored
+    }
+}
+
+/// Collects reported nodes into a tree for formats that are rendered after all
+/// benchmarks have finished.
+#[derive(Default)]
+pub(crate) struct TreeBuilder {
+    /// Top-level nodes.
+    roots: Vec<ReportNode>,
+
+    /// Nodes that have been started but not finished.
+    stack: Vec<ReportNode>,
+}
+
+impl TreeBuilder {
+    /// Takes the top-level nodes collected so far.
+    pub fn take_roots(&mut self) -> Vec<ReportNode> {
+        std::mem::take(&mut self.roots)
+    }
+
+    /// Takes the nodes collected so far as a [`Report`].
+    pub fn take_report(&mut self) -> Report {
+        Report { roots: self.take_roots() }
+    }
+
+    fn push_finished(&mut self, node: ReportNode) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.roots.push(node),
+        }
+    }
+
+    fn pop_finished(&mut self) {
+        if let Some(node) = self.stack.pop() {
+            self.push_finished(node);
+        }
+    }
+}
+
+impl Reporter for TreeBuilder {
+    fn start_parent(&mut self, node: &Node) {
+        self.stack.push(ReportNode::new(node));
+    }
+
+    fn finish_parent(&mut self) {
+        self.pop_finished();
+    }
+
+    fn ignore_leaf(&mut self, node: &Node) {
+        self.push_finished(ReportNode { ignored: true, ..ReportNode::new(node) });
+    }
+
+    fn start_leaf(&mut self, node: &Node) {
+        self.stack.push(ReportNode::new(node));
+    }
+
+    fn finish_empty_leaf(&mut self) {
+        self.pop_finished();
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        if let Some(node) = self.stack.last_mut() {
+            node.stats = Some(stats.clone());
+        }
+        self.pop_finished();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node<'a>(name: &'a str, path: &'a str) -> Node<'a> {
+        Node {
+            name,
+            path,
+            kind: NodeKind::Bench,
+            is_last: false,
+            baseline: None,
+            thread_count: None,
+        }
+    }
+
+    #[test]
+    fn report_get() {
+        let mut builder = TreeBuilder::default();
+        builder.start_parent(&node("a", "a"));
+        builder.start_leaf(&node("b", "a::b"));
+        builder.finish_empty_leaf();
+        builder.start_leaf(&node("bc", "a::bc"));
+        builder.finish_empty_leaf();
+        builder.finish_parent();
+        builder.ignore_leaf(&node("ab", "ab"));
+
+        let report = builder.take_report();
+
+        assert_eq!(report.get("a").map(ReportNode::path), Some("a"));
+        assert_eq!(report.get("a::b").map(ReportNode::path), Some("a::b"));
+        assert_eq!(report.get("a::bc").map(ReportNode::path), Some("a::bc"));
+        assert_eq!(report.get("ab").map(ReportNode::is_ignored), Some(true));
+        assert!(report.get("a::c").is_none());
+        assert!(report.get("a::").is_none());
+
+        let leaves: Vec<&str> = report.leaves().map(ReportNode::path).collect();
+        assert_eq!(leaves, ["a::b", "a::bc", "ab"]);
+    }
+}
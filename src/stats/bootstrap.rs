@@ -0,0 +1,139 @@
+//! Bootstrap confidence intervals.
+
+use super::{percentile, ConfidenceInterval};
+
+/// Options for estimating confidence intervals by resampling.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Bootstrap {
+    /// Fraction of resampled estimates within each interval, such as `0.95`.
+    pub confidence_level: f64,
+
+    /// Number of times samples are resampled. Intervals are not estimated if
+    /// this is 0.
+    pub resamples: u32,
+}
+
+impl Default for Bootstrap {
+    fn default() -> Self {
+        Self { confidence_level: 0.95, resamples: 10_000 }
+    }
+}
+
+/// Confidence intervals of the median and mean of samples.
+pub(crate) struct BootstrapIntervals {
+    pub median: ConfidenceInterval<f64>,
+    pub mean: ConfidenceInterval<f64>,
+}
+
+impl Bootstrap {
+    /// Estimates confidence intervals of the median and mean of `samples` by
+    /// the percentile method.
+    ///
+    /// Resampling is seeded with a constant so that the same samples always
+    /// produce the same intervals.
+    pub fn estimate(&self, samples: &[f64]) -> Option<BootstrapIntervals> {
+        if self.resamples == 0 || samples.len() < 2 {
+            return None;
+        }
+
+        let mut rng = SplitMix64(0x6469_7661_6e5f_6369);
+        let mut resample = vec![0.0; samples.len()];
+        let mut medians = Vec::with_capacity(self.resamples as usize);
+        let mut means = Vec::with_capacity(self.resamples as usize);
+
+        for _ in 0..self.resamples {
+            let mut sum = 0.0;
+            for value in &mut resample {
+                *value = samples[rng.next_index(samples.len())];
+                sum += *value;
+            }
+
+            means.push(sum / samples.len() as f64);
+            medians.push(median(&mut resample));
+        }
+
+        medians.sort_unstable_by(f64::total_cmp);
+        means.sort_unstable_by(f64::total_cmp);
+
+        let interval = |sorted: &[f64]| ConfidenceInterval {
+            lower: percentile(sorted, (1.0 - self.confidence_level) / 2.0),
+            upper: percentile(sorted, (1.0 + self.confidence_level) / 2.0),
+            level: self.confidence_level,
+        };
+
+        Some(BootstrapIntervals { median: interval(&medians), mean: interval(&means) })
+    }
+}
+
+/// Returns the median of non-empty `values`, reordering them in the process.
+fn median(values: &mut [f64]) -> f64 {
+    let is_odd = values.len() % 2 == 1;
+    let (lower, &mut upper, _) = values.select_nth_unstable_by(values.len() / 2, f64::total_cmp);
+
+    if is_odd {
+        upper
+    } else {
+        let lower = lower.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        (lower + upper) / 2.0
+    }
+}
+
+/// Small and fast pseudorandom number generator, which is sufficient for
+/// resampling.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns an index in `0..len` with negligible bias.
+    fn next_index(&mut self, len: usize) -> usize {
+        ((self.next_u64() as u128 * len as u128) >> 64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median() {
+        assert_eq!(super::median(&mut [3.0]), 3.0);
+        assert_eq!(super::median(&mut [4.0, 1.0, 3.0]), 3.0);
+        assert_eq!(super::median(&mut [4.0, 1.0, 2.0, 3.0]), 2.5);
+    }
+
+    #[test]
+    fn estimate() {
+        let bootstrap = Bootstrap::default();
+
+        assert!(bootstrap.estimate(&[1.0]).is_none());
+        assert!(Bootstrap { resamples: 0, ..bootstrap }.estimate(&[1.0, 2.0]).is_none());
+
+        // Identical samples have no uncertainty.
+        let flat = bootstrap.estimate(&[5.0; 10]).unwrap();
+        assert_eq!((flat.median.lower, flat.median.upper), (5.0, 5.0));
+        assert_eq!((flat.mean.lower, flat.mean.upper), (5.0, 5.0));
+
+        let samples: Vec<f64> = (1..=100).map(f64::from).collect();
+        let intervals = bootstrap.estimate(&samples).unwrap();
+        assert_eq!(intervals.median.level, 0.95);
+
+        for interval in [intervals.median, intervals.mean] {
+            assert!(interval.lower < 50.5 && interval.upper > 50.5);
+            assert!(interval.lower > 35.0 && interval.upper < 66.0);
+        }
+
+        // Lower confidence gives narrower intervals.
+        let narrow = Bootstrap { confidence_level: 0.5, ..bootstrap }.estimate(&samples).unwrap();
+        assert!(
+            narrow.mean.upper - narrow.mean.lower < intervals.mean.upper - intervals.mean.lower
+        );
+    }
+}
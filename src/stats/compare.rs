@@ -0,0 +1,149 @@
+//! Comparison of statistics between runs.
+
+/// Changes in median or mean times smaller than this fraction are considered
+/// noise, even if statistically significant.
+const NOISE_THRESHOLD: f64 = 0.02;
+
+/// The Mann-Whitney U test z-score above which a change is significant, which
+/// corresponds to a two-sided p-value of 0.05.
+const SIGNIFICANCE_Z: f64 = 1.96;
+
+/// Summary of how times changed from an old run to a new run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Comparison {
+    /// Fractional change in median time, where `-0.1` is 10% faster.
+    pub median_change: f64,
+
+    /// Fractional change in mean time, where `-0.1` is 10% faster.
+    pub mean_change: f64,
+
+    pub verdict: Verdict,
+}
+
+/// Whether a benchmark became faster or slower.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    Faster,
+    Slower,
+    NoChange,
+}
+
+impl Verdict {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Faster => "faster",
+            Self::Slower => "slower",
+            Self::NoChange => "no change",
+        }
+    }
+}
+
+/// Times of a single run, in nanoseconds.
+pub(crate) struct RunTimes<'a> {
+    pub median: f64,
+    pub mean: f64,
+
+    /// Per-iteration time of each sample.
+    pub samples: &'a [f64],
+}
+
+impl Comparison {
+    pub fn new(old: &RunTimes, new: &RunTimes) -> Self {
+        let change = |old: f64, new: f64| if old > 0.0 { new / old - 1.0 } else { 0.0 };
+
+        let median_change = change(old.median, new.median);
+        let mean_change = change(old.mean, new.mean);
+
+        let is_significant = median_change.abs() >= NOISE_THRESHOLD
+            && mann_whitney_z(old.samples, new.samples).abs() >= SIGNIFICANCE_Z;
+
+        let verdict = if !is_significant {
+            Verdict::NoChange
+        } else if median_change < 0.0 {
+            Verdict::Faster
+        } else {
+            Verdict::Slower
+        };
+
+        Self { median_change, mean_change, verdict }
+    }
+}
+
+/// Returns the z-score of the Mann-Whitney U test, which is positive if `b`
+/// tends to be greater than `a`.
+///
+/// This uses the normal approximation with averaged ranks for ties, which is
+/// reasonable for the sample counts typically used.
+fn mann_whitney_z(a: &[f64], b: &[f64]) -> f64 {
+    let (n_a, n_b) = (a.len() as f64, b.len() as f64);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    // Pair each value with whether it is from `b`, sorted by value.
+    let mut values: Vec<(f64, bool)> =
+        a.iter().map(|&v| (v, false)).chain(b.iter().map(|&v| (v, true))).collect();
+    values.sort_unstable_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Sum of ranks of values from `b`, with tied values sharing their
+    // average rank.
+    let mut rank_sum_b = 0.0;
+    let mut i = 0;
+    while i < values.len() {
+        let tie_len = values[i..].iter().take_while(|v| v.0 == values[i].0).count();
+
+        // Ranks are 1-based.
+        let avg_rank = i as f64 + (tie_len as f64 + 1.0) / 2.0;
+        let b_count = values[i..i + tie_len].iter().filter(|v| v.1).count();
+        rank_sum_b += avg_rank * b_count as f64;
+
+        i += tie_len;
+    }
+
+    let u_b = rank_sum_b - n_b * (n_b + 1.0) / 2.0;
+    let mean = n_a * n_b / 2.0;
+    let std_dev = (n_a * n_b * (n_a + n_b + 1.0) / 12.0).sqrt();
+
+    if std_dev == 0.0 {
+        0.0
+    } else {
+        (u_b - mean) / std_dev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mann_whitney() {
+        let a: Vec<f64> = (0..20).map(f64::from).collect();
+        let b: Vec<f64> = (100..120).map(f64::from).collect();
+
+        assert!(mann_whitney_z(&a, &b) > SIGNIFICANCE_Z);
+        assert!(mann_whitney_z(&b, &a) < -SIGNIFICANCE_Z);
+        assert_eq!(mann_whitney_z(&a, &a), 0.0);
+        assert_eq!(mann_whitney_z(&a, &[]), 0.0);
+    }
+
+    #[test]
+    fn verdict() {
+        let samples: Vec<f64> = (0..20).map(|i| 10.0 + f64::from(i) / 100.0).collect();
+        let slower: Vec<f64> = samples.iter().map(|s| s * 1.5).collect();
+        let noisy: Vec<f64> = samples.iter().map(|s| s * 1.01).collect();
+
+        let times = |samples| RunTimes { median: 10.1, mean: 10.1, samples };
+        let scaled =
+            |samples, scale: f64| RunTimes { median: 10.1 * scale, mean: 10.1 * scale, samples };
+
+        let cmp = Comparison::new(&times(&samples), &scaled(&slower, 1.5));
+        assert_eq!(cmp.verdict, Verdict::Slower);
+        assert!((cmp.median_change - 0.5).abs() < 1e-9);
+
+        let cmp = Comparison::new(&scaled(&slower, 1.5), &times(&samples));
+        assert_eq!(cmp.verdict, Verdict::Faster);
+
+        let cmp = Comparison::new(&times(&samples), &scaled(&noisy, 1.01));
+        assert_eq!(cmp.verdict, Verdict::NoChange);
+    }
+}
@@ -1,42 +1,218 @@
 //! Measurement statistics.
 
+use std::ops::Range;
+
 use crate::{
-    alloc::{AllocOpMap, AllocTally},
-    counter::{KnownCounterKind, MaxCountUInt},
+    alloc::{AllocOp, AllocOpMap, AllocTally},
+    counter::{Counter, KnownCounterKind, MaxCountUInt},
     time::FineDuration,
 };
 
+mod bootstrap;
+mod compare;
 mod sample;
 
+pub(crate) use bootstrap::*;
+pub(crate) use compare::*;
 pub(crate) use sample::*;
 
-/// Statistics from samples.
-pub(crate) struct Stats {
+/// Statistics from samples of a benchmark.
+///
+/// This is provided to [`Reporter::finish_leaf`](crate::report::Reporter::finish_leaf).
+#[derive(Clone, Debug)]
+pub struct Stats {
     /// Total number of samples taken.
-    pub sample_count: u32,
+    pub(crate) sample_count: u32,
 
     /// Total number of iterations (currently `sample_count * `sample_size`).
-    pub iter_count: u64,
+    pub(crate) iter_count: u64,
 
     /// Timing statistics.
-    pub time: StatsSet<FineDuration>,
+    pub(crate) time: StatsSet<FineDuration>,
+
+    /// Percentiles and dispersion of iteration times.
+    pub(crate) time_distribution: Distribution<FineDuration>,
+
+    /// Samples outside of Tukey's fences.
+    pub(crate) outliers: Outliers,
+
+    /// Least-squares fit of sample times over sample sizes, if sizes varied.
+    pub(crate) regression: Option<Regression>,
+
+    /// Bootstrap confidence interval of the median iteration time.
+    pub(crate) median_ci: Option<ConfidenceInterval<FineDuration>>,
+
+    /// Bootstrap confidence interval of the mean iteration time.
+    pub(crate) mean_ci: Option<ConfidenceInterval<FineDuration>>,
 
     /// Allocation statistics associated with the corresponding samples for
     /// `time`.
-    pub alloc_tallies: AllocOpMap<AllocTally<StatsSet<f64>>>,
+    pub(crate) alloc_tallies: AllocOpMap<AllocTally<StatsSet<f64>>>,
 
     /// `Counter` counts associated with the corresponding samples for `time`.
-    pub counts: [Option<StatsSet<MaxCountUInt>>; KnownCounterKind::COUNT],
+    pub(crate) counts: [Option<StatsSet<MaxCountUInt>>; KnownCounterKind::COUNT],
+
+    /// Per-iteration duration of each sample, in the order they were taken.
+    pub(crate) samples: Vec<FineDuration>,
+}
+
+/// Public read-only access.
+impl Stats {
+    /// Returns the total number of samples taken.
+    #[inline]
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Returns the total number of iterations across all samples.
+    #[inline]
+    pub fn iter_count(&self) -> u64 {
+        self.iter_count
+    }
+
+    /// Returns the time taken by an iteration, in nanoseconds.
+    ///
+    /// Nanoseconds are fractional because the fastest benchmarks can take less
+    /// than a nanosecond per iteration.
+    pub fn time_nanos(&self) -> StatsSet<f64> {
+        self.time.map(|time| time.as_nanos_f64())
+    }
+
+    /// Returns percentiles and dispersion of the time taken by an iteration,
+    /// in nanoseconds.
+    pub fn time_distribution_nanos(&self) -> Distribution<f64> {
+        self.time_distribution.map(|time| time.as_nanos_f64())
+    }
+
+    /// Returns the number of samples whose iteration time is unusually low or
+    /// high.
+    pub fn outliers(&self) -> Outliers {
+        self.outliers
+    }
+
+    /// Returns the least-squares fit of sample times over sample sizes, if
+    /// sample sizes varied with [`Sampling::Linear`](crate::Sampling::Linear).
+    pub fn regression(&self) -> Option<Regression> {
+        self.regression
+    }
+
+    /// Returns the confidence interval of the median time taken by an
+    /// iteration, in nanoseconds.
+    ///
+    /// This is [`None`] if there were too few samples or if intervals were
+    /// disabled with [`Divan::bootstrap_resamples`](crate::Divan::bootstrap_resamples).
+    pub fn median_ci_nanos(&self) -> Option<ConfidenceInterval<f64>> {
+        self.median_ci.map(|ci| ci.map(|time| time.as_nanos_f64()))
+    }
+
+    /// Returns the confidence interval of the mean time taken by an iteration,
+    /// in nanoseconds.
+    ///
+    /// This is [`None`] if there were too few samples or if intervals were
+    /// disabled with [`Divan::bootstrap_resamples`](crate::Divan::bootstrap_resamples).
+    pub fn mean_ci_nanos(&self) -> Option<ConfidenceInterval<f64>> {
+        self.mean_ci.map(|ci| ci.map(|time| time.as_nanos_f64()))
+    }
+
+    /// Returns the time taken by an iteration of each sample in nanoseconds,
+    /// in the order the samples were taken.
+    pub fn sample_nanos(&self) -> impl ExactSizeIterator<Item = f64> + '_ {
+        self.samples.iter().map(|time| time.as_nanos_f64())
+    }
+
+    /// Returns the number of values processed per second, if counter `C` was
+    /// used.
+    pub fn throughput<C: Counter>(&self) -> Option<StatsSet<f64>> {
+        self.get_throughput(KnownCounterKind::of::<C>())
+    }
+
+    /// Returns the number of times `op` was performed per iteration.
+    ///
+    /// This is only nonzero when [`AllocProfiler`](crate::AllocProfiler) is
+    /// the global allocator.
+    pub fn alloc_count(&self, op: AllocOp) -> StatsSet<f64> {
+        self.alloc_tallies.get(op).count
+    }
+
+    /// Returns the number of bytes changed by `op` per iteration.
+    ///
+    /// This is only nonzero when [`AllocProfiler`](crate::AllocProfiler) is
+    /// the global allocator.
+    pub fn alloc_size(&self, op: AllocOp) -> StatsSet<f64> {
+        self.alloc_tallies.get(op).size
+    }
 }
 
 impl Stats {
-    pub fn get_counts(&self, counter_kind: KnownCounterKind) -> Option<&StatsSet<MaxCountUInt>> {
+    pub(crate) fn get_counts(
+        &self,
+        counter_kind: KnownCounterKind,
+    ) -> Option<&StatsSet<MaxCountUInt>> {
         self.counts[counter_kind as usize].as_ref()
     }
+
+    /// Returns the per-second throughput of `counter_kind`, computed from each
+    /// count and its corresponding time.
+    pub(crate) fn get_throughput(&self, counter_kind: KnownCounterKind) -> Option<StatsSet<f64>> {
+        let counts = self.get_counts(counter_kind)?;
+        let throughput =
+            |count: MaxCountUInt, time: FineDuration| count as f64 / time.as_secs_f64();
+
+        Some(StatsSet {
+            fastest: throughput(counts.fastest, self.time.fastest),
+            slowest: throughput(counts.slowest, self.time.slowest),
+            median: throughput(counts.median, self.time.median),
+            mean: throughput(counts.mean, self.time.mean),
+        })
+    }
+}
+
+#[cfg(test)]
+impl Stats {
+    /// Returns stats without samples whose iteration times are all
+    /// `median_nanos`, except for the mean.
+    ///
+    /// Tests can set other fields with struct update syntax.
+    pub(crate) fn test(median_nanos: f64, mean_nanos: f64) -> Self {
+        let median = FineDuration::from_nanos_f64(median_nanos);
+        let zero = StatsSet { fastest: 0.0, slowest: 0.0, median: 0.0, mean: 0.0 };
+
+        Self {
+            sample_count: 0,
+            iter_count: 0,
+            time: StatsSet {
+                fastest: median,
+                slowest: median,
+                median,
+                mean: FineDuration::from_nanos_f64(mean_nanos),
+            },
+            time_distribution: Distribution::default(),
+            outliers: Outliers::default(),
+            regression: None,
+            median_ci: None,
+            mean_ci: None,
+            alloc_tallies: AllocOpMap::from_fn(|_| AllocTally { count: zero, size: zero }),
+            counts: Default::default(),
+            samples: Vec::new(),
+        }
+    }
+
+    /// Sets the count of `counter_kind` per iteration.
+    pub(crate) fn with_count(
+        mut self,
+        counter_kind: KnownCounterKind,
+        count: MaxCountUInt,
+    ) -> Self {
+        self.counts[counter_kind as usize] =
+            Some(StatsSet { fastest: count, slowest: count, median: count, mean: count });
+        self
+    }
 }
 
-#[derive(Debug)]
-pub(crate) struct StatsSet<T> {
+/// Statistics associated with the fastest, slowest, median, and mean
+/// iteration times of a benchmark.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatsSet<T> {
     /// Associated with minimum amount of time taken by an iteration.
     pub fastest: T,
 
@@ -50,8 +226,319 @@ pub(crate) struct StatsSet<T> {
     pub mean: T,
 }
 
+impl<T> StatsSet<T> {
+    #[inline]
+    pub(crate) fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> StatsSet<U> {
+        StatsSet {
+            fastest: f(&self.fastest),
+            slowest: f(&self.slowest),
+            median: f(&self.median),
+            mean: f(&self.mean),
+        }
+    }
+}
+
 impl StatsSet<f64> {
-    pub fn is_zero(&self) -> bool {
+    pub(crate) fn is_zero(&self) -> bool {
         self.fastest == 0.0 && self.slowest == 0.0 && self.median == 0.0 && self.mean == 0.0
     }
 }
+
+/// Percentiles and dispersion of iteration times, which describe tail latency
+/// and noise better than [`StatsSet`].
+///
+/// Percentiles are interpolated linearly between the closest samples.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Distribution<T> {
+    /// 5th percentile.
+    pub p5: T,
+
+    /// 25th percentile, or first quartile.
+    pub p25: T,
+
+    /// 75th percentile, or third quartile.
+    pub p75: T,
+
+    /// 95th percentile.
+    pub p95: T,
+
+    /// 99th percentile.
+    pub p99: T,
+
+    /// Sample standard deviation.
+    pub std_dev: T,
+
+    /// Median absolute deviation from the median.
+    ///
+    /// This is not scaled to estimate the standard deviation.
+    pub mad: T,
+}
+
+impl<T> Distribution<T> {
+    #[inline]
+    pub(crate) fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Distribution<U> {
+        Distribution {
+            p5: f(&self.p5),
+            p25: f(&self.p25),
+            p75: f(&self.p75),
+            p95: f(&self.p95),
+            p99: f(&self.p99),
+            std_dev: f(&self.std_dev),
+            mad: f(&self.mad),
+        }
+    }
+}
+
+impl Distribution<f64> {
+    /// Computes the distribution of values sorted in ascending order.
+    pub(crate) fn from_sorted(sorted: &[f64]) -> Self {
+        if sorted.is_empty() {
+            return Self::default();
+        }
+
+        let len = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / len;
+
+        let std_dev = if sorted.len() > 1 {
+            let sum_squares: f64 = sorted.iter().map(|value| (value - mean).powi(2)).sum();
+            (sum_squares / (len - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+
+        let median = percentile(sorted, 0.5);
+        let mut deviations: Vec<f64> = sorted.iter().map(|value| (value - median).abs()).collect();
+        deviations.sort_unstable_by(f64::total_cmp);
+
+        Self {
+            p5: percentile(sorted, 0.05),
+            p25: percentile(sorted, 0.25),
+            p75: percentile(sorted, 0.75),
+            p95: percentile(sorted, 0.95),
+            p99: percentile(sorted, 0.99),
+            std_dev,
+            mad: percentile(&deviations, 0.5),
+        }
+    }
+}
+
+/// Number of samples outside of Tukey's fences, which are placed 1.5 (mild)
+/// and 3 (severe) interquartile ranges below the first quartile and above the
+/// third quartile of iteration times.
+///
+/// Outliers are often caused by interference such as preemption by other
+/// processes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Outliers {
+    /// Samples below the outer lower fence.
+    pub low_severe: u32,
+
+    /// Samples between the outer and inner lower fences.
+    pub low_mild: u32,
+
+    /// Samples between the inner and outer upper fences.
+    pub high_mild: u32,
+
+    /// Samples above the outer upper fence.
+    pub high_severe: u32,
+}
+
+impl Outliers {
+    /// Returns the total number of outliers.
+    #[inline]
+    pub fn total(&self) -> u32 {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+
+    /// Classifies values sorted in ascending order.
+    pub(crate) fn from_sorted(sorted: &[f64]) -> Self {
+        if sorted.is_empty() {
+            return Self::default();
+        }
+
+        let q1 = percentile(sorted, 0.25);
+        let q3 = percentile(sorted, 0.75);
+        let iqr = q3 - q1;
+
+        let count =
+            |f: &dyn Fn(f64) -> bool| sorted.iter().filter(|&&value| f(value)).count() as u32;
+
+        let (mild_low, severe_low) = (q1 - 1.5 * iqr, q1 - 3.0 * iqr);
+        let (mild_high, severe_high) = (q3 + 1.5 * iqr, q3 + 3.0 * iqr);
+
+        Self {
+            low_severe: count(&|value| value < severe_low),
+            low_mild: count(&|value| value < mild_low && value >= severe_low),
+            high_mild: count(&|value| value > mild_high && value <= severe_high),
+            high_severe: count(&|value| value > severe_high),
+        }
+    }
+
+    /// Returns the index range of sorted values that are not outliers.
+    pub(crate) fn inliers(&self, len: usize) -> Range<usize> {
+        let low = (self.low_severe + self.low_mild) as usize;
+        let high = (self.high_mild + self.high_severe) as usize;
+        low..len - high
+    }
+
+    /// Summarizes the number of outliers, such as `3 (1 severe)`.
+    pub(crate) fn summary(&self) -> String {
+        let severe = self.low_severe + self.high_severe;
+        if severe == 0 {
+            self.total().to_string()
+        } else {
+            format!("{} ({severe} severe)", self.total())
+        }
+    }
+}
+
+/// Least-squares fit of sample times over sample sizes.
+///
+/// Constant overhead per sample, such as from reading the timer, only affects
+/// the intercept of the fit and is thus excluded from the slope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Regression {
+    /// Estimated time taken by an iteration, in nanoseconds.
+    pub slope: f64,
+
+    /// Coefficient of determination, where 1 means that sample times are
+    /// perfectly proportional to sample sizes.
+    pub r_squared: f64,
+}
+
+impl Regression {
+    /// Fits `(size, nanos)` points, or returns [`None`] if sizes do not vary.
+    pub(crate) fn fit(points: &[(f64, f64)]) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+
+        let len = points.len() as f64;
+        let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / len;
+        let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / len;
+
+        let (mut sum_xx, mut sum_xy, mut sum_yy) = (0.0, 0.0, 0.0);
+        for &(x, y) in points {
+            let (dx, dy) = (x - mean_x, y - mean_y);
+            sum_xx += dx * dx;
+            sum_xy += dx * dy;
+            sum_yy += dy * dy;
+        }
+
+        if sum_xx <= 0.0 {
+            return None;
+        }
+
+        let slope = sum_xy / sum_xx;
+
+        // Times that do not vary are perfectly explained by sizes.
+        let r_squared = if sum_yy > 0.0 { sum_xy * sum_xy / (sum_xx * sum_yy) } else { 1.0 };
+
+        Some(Self { slope, r_squared })
+    }
+}
+
+/// Range that likely contains the true value of an estimate, as computed by
+/// resampling the samples of a benchmark.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct ConfidenceInterval<T> {
+    /// Lower bound.
+    pub lower: T,
+
+    /// Upper bound.
+    pub upper: T,
+
+    /// Fraction of resampled estimates within the bounds, such as `0.95` for
+    /// a 95% confidence interval.
+    pub level: f64,
+}
+
+impl<T> ConfidenceInterval<T> {
+    #[inline]
+    pub(crate) fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> ConfidenceInterval<U> {
+        ConfidenceInterval { lower: f(&self.lower), upper: f(&self.upper), level: self.level }
+    }
+}
+
+impl ConfidenceInterval<f64> {
+    /// Returns half the width of the interval as a fraction of `estimate`,
+    /// for showing as `estimate ± margin`.
+    pub(crate) fn relative_margin(&self, estimate: f64) -> Option<f64> {
+        (estimate > 0.0).then(|| (self.upper - self.lower) / 2.0 / estimate)
+    }
+}
+
+/// Returns the value at fraction `p` of non-empty `sorted`, interpolating
+/// between the closest ranks.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribution() {
+        assert_eq!(Distribution::from_sorted(&[]), Distribution::default());
+
+        let single = Distribution::from_sorted(&[3.0]);
+        assert_eq!((single.p5, single.p99, single.std_dev, single.mad), (3.0, 3.0, 0.0, 0.0));
+
+        // 0 through 100.
+        let values: Vec<f64> = (0..=100).map(f64::from).collect();
+        let dist = Distribution::from_sorted(&values);
+        assert_eq!(
+            [dist.p5, dist.p25, dist.p75, dist.p95, dist.p99],
+            [5.0, 25.0, 75.0, 95.0, 99.0]
+        );
+        assert_eq!(dist.mad, 25.0);
+        assert!((dist.std_dev - 29.300170647967224).abs() < 1e-9);
+
+        // Interpolated between ranks.
+        let dist = Distribution::from_sorted(&[1.0, 2.0, 4.0, 8.0]);
+        assert_eq!(dist.p25, 1.75);
+        assert_eq!(dist.p75, 5.0);
+        assert_eq!(dist.mad, 1.5);
+    }
+
+    #[test]
+    fn regression() {
+        assert_eq!(Regression::fit(&[]), None);
+        assert_eq!(Regression::fit(&[(2.0, 1.0), (2.0, 3.0)]), None);
+
+        // Constant overhead of 10 does not affect the slope.
+        let exact = Regression::fit(&[(1.0, 12.0), (2.0, 14.0), (3.0, 16.0)]).unwrap();
+        assert_eq!(exact, Regression { slope: 2.0, r_squared: 1.0 });
+
+        let noisy = Regression::fit(&[(1.0, 1.0), (2.0, 3.0), (3.0, 2.0)]).unwrap();
+        assert_eq!(noisy.slope, 0.5);
+        assert_eq!(noisy.r_squared, 0.25);
+    }
+
+    #[test]
+    fn outliers() {
+        assert_eq!(Outliers::from_sorted(&[]), Outliers::default());
+
+        // Quartiles are 12 and 18, so mild fences are at 3 and 27, and severe
+        // fences are at -6 and 36.
+        let mut values = vec![-30.0, 0.0, 30.0, 40.0, 60.0];
+        values.extend((10..=20).map(f64::from).flat_map(|value| [value; 3]));
+        values.sort_unstable_by(f64::total_cmp);
+
+        let outliers = Outliers::from_sorted(&values);
+        assert_eq!(outliers, Outliers { low_severe: 1, low_mild: 1, high_mild: 1, high_severe: 2 });
+        assert_eq!(outliers.total(), 5);
+        assert_eq!(outliers.summary(), "5 (3 severe)");
+
+        let inliers = &values[outliers.inliers(values.len())];
+        assert_eq!((inliers[0], inliers[inliers.len() - 1]), (10.0, 20.0));
+    }
+}
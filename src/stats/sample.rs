@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use crate::{
     alloc::ThreadAllocTallyMap,
     counter::KnownCounterKind,
+    stats::{percentile, Bootstrap},
     time::{FineDuration, Timer, Timestamp},
 };
 
@@ -13,6 +14,17 @@ pub(crate) struct TimeSample {
     /// This is gotten from [`RawSample`] with:
     /// `end.duration_since(start, timer).clamp_to(timer.precision())`.
     pub duration: FineDuration,
+
+    /// The number of iterations within this sample.
+    pub sample_size: u32,
+}
+
+impl TimeSample {
+    /// Returns the time taken by an iteration of this sample.
+    #[inline]
+    pub fn iter_duration(&self) -> FineDuration {
+        self.duration / self.sample_size
+    }
 }
 
 /// Unprocessed measurement.
@@ -51,9 +63,6 @@ impl RawSample {
 /// Sample collection.
 #[derive(Default)]
 pub(crate) struct SampleCollection {
-    /// The number of iterations within each sample.
-    pub sample_size: u32,
-
     /// Collected timings.
     pub time_samples: Vec<TimeSample>,
 
@@ -81,7 +90,7 @@ impl SampleCollection {
     /// We use `u64` in case sample count and sizes are huge.
     #[inline]
     pub fn iter_count(&self) -> u64 {
-        self.sample_size as u64 * self.time_samples.len() as u64
+        self.time_samples.iter().map(|s| s.sample_size as u64).sum()
     }
 
     /// Computes the total time across all samples.
@@ -90,11 +99,29 @@ impl SampleCollection {
         FineDuration { picos: self.time_samples.iter().map(|s| s.duration.picos).sum() }
     }
 
-    /// Returns all samples sorted by duration.
+    /// Estimates half the width of the confidence interval around the median
+    /// time per iteration, as a fraction of the median.
+    pub fn median_margin(&self, bootstrap: &Bootstrap) -> Option<f64> {
+        let mut nanos: Vec<f64> =
+            self.time_samples.iter().map(|s| s.iter_duration().as_nanos_f64()).collect();
+
+        let interval = bootstrap.estimate(&nanos)?.median;
+
+        nanos.sort_unstable_by(f64::total_cmp);
+        interval.relative_margin(percentile(&nanos, 0.5))
+    }
+
+    /// Returns all samples sorted by duration per iteration.
     #[inline]
     pub fn sorted_samples(&self) -> Vec<&TimeSample> {
         let mut result: Vec<&TimeSample> = self.time_samples.iter().collect();
-        result.sort_unstable_by_key(|s| s.duration);
+
+        // Compare exactly rather than with rounded `TimeSample::iter_duration`.
+        result.sort_unstable_by(|a, b| {
+            let a_picos = a.duration.picos.saturating_mul(b.sample_size as u128);
+            let b_picos = b.duration.picos.saturating_mul(a.sample_size as u128);
+            a_picos.cmp(&b_picos)
+        });
         result
     }
 }
@@ -112,6 +112,23 @@ impl FineDuration {
         self.picos == 0
     }
 
+    /// Creates a duration from fractional nanoseconds, rounded to the nearest
+    /// picosecond.
+    #[inline]
+    pub fn from_nanos_f64(nanos: f64) -> Self {
+        Self { picos: (nanos * picos::NANOS as f64).round() as u128 }
+    }
+
+    #[inline]
+    pub fn as_nanos_f64(self) -> f64 {
+        self.picos as f64 / picos::NANOS as f64
+    }
+
+    #[inline]
+    pub fn as_secs_f64(self) -> f64 {
+        self.picos as f64 / picos::SEC as f64
+    }
+
     /// Round up to `other` if `self` is zero.
     #[inline]
     pub fn clamp_to(self, other: Self) -> Self {
@@ -5,19 +5,27 @@ use std::{io::Write, iter::repeat};
 use crate::{
     alloc::{AllocOp, AllocTally},
     counter::{AnyCounter, BytesFormat, KnownCounterKind},
-    stats::{Stats, StatsSet},
+    report::{sparkline, Baseline, Column, History, Node, Reporter},
+    stats::{Comparison, ConfidenceInterval, Distribution, RunTimes, Stats, StatsSet},
+    time::FineDuration,
     util,
 };
 
 const TREE_COL_BUF: usize = 2;
 
+/// The number of bins in histograms of sample times.
+const HISTOGRAM_BINS: usize = 32;
+
 /// Paints tree-style output using box-drawing characters.
-pub(crate) struct TreePainter {
+pub(crate) struct TreePainter<'a> {
     /// The maximum number of characters taken by a name and its prefix. Emitted
     /// information should be left-padded to start at this column.
     max_name_span: usize,
 
-    column_widths: [usize; TreeColumn::COUNT],
+    columns: ColumnSet,
+
+    /// Widths of each column in `columns`.
+    column_widths: Vec<usize>,
 
     depth: usize,
 
@@ -28,26 +36,292 @@ pub(crate) struct TreePainter {
 
     /// Buffer for writing to before printing to stdout.
     write_buf: String,
+
+    /// Whether the current leaf is the last child of its parent.
+    is_last_leaf: bool,
+
+    bytes_format: BytesFormat,
+
+    /// Results to compare against, each in an extra column after the table.
+    baselines: Vec<&'a Baseline>,
+
+    /// Whether to show an extra column comparing leaves against their
+    /// `#[divan::bench(baseline = ...)]` benchmark.
+    has_relative: bool,
+
+    /// Previous runs and the number of runs to show in a sparkline of median
+    /// times, including the current run.
+    trend: Option<(&'a History, usize)>,
+
+    /// Widths of the extra columns after the table.
+    extra_widths: Vec<usize>,
+
+    /// The full path of the current leaf, used to look up `trend`.
+    leaf_path: String,
+
+    /// The key of the current leaf in `baselines`.
+    leaf_baseline_key: String,
+
+    /// The median time in nanoseconds of the current leaf's `baseline`
+    /// benchmark.
+    leaf_baseline_median: Option<f64>,
+
+    /// Whether to show each leaf's median time as a multiple of its fastest
+    /// sibling.
+    ///
+    /// This buffers output in `pending_leaves` until all siblings have stats.
+    relative_to_fastest: bool,
+
+    /// Output of each parent being written that has not been printed yet,
+    /// starting with top-level nodes.
+    pending_leaves: Vec<Vec<PendingLeaf>>,
+
+    /// Whether to draw a histogram of sample times below each leaf.
+    histogram: bool,
+}
+
+/// Output whose printing is delayed until all of its siblings have stats.
+enum PendingLeaf {
+    /// Lines that do not depend on siblings, such as for ignored leaves or
+    /// finished parents, including the trailing newline.
+    Lines(String),
+
+    /// A leaf with stats.
+    Stats {
+        /// The tree prefix and padded name of the leaf.
+        name_line: String,
+        is_last: bool,
+        path: String,
+        baseline_key: String,
+        baseline_median: Option<f64>,
+        stats: Box<Stats>,
+    },
+}
+
+/// Extra information shown by [`TreePainter`] alongside its table.
+#[derive(Default)]
+pub(crate) struct TreeExtras<'a> {
+    /// Results to compare against, each in an extra column after the table.
+    pub baselines: Vec<&'a Baseline>,
+
+    /// Whether to show an extra column comparing leaves against their
+    /// `#[divan::bench(baseline = ...)]` benchmark.
+    pub has_relative: bool,
+
+    /// Whether to show each leaf's median time as a multiple of its fastest
+    /// sibling.
+    pub relative_to_fastest: bool,
+
+    /// Previous runs and the number of runs to show in a sparkline of median
+    /// times, including the current run.
+    pub trend: Option<(&'a History, usize)>,
+
+    /// Whether to draw a histogram of sample times below each leaf.
+    pub histogram: bool,
 }
 
-impl TreePainter {
-    pub fn new(max_name_span: usize, column_widths: [usize; TreeColumn::COUNT]) -> Self {
+impl<'a> TreePainter<'a> {
+    pub fn new(
+        max_name_span: usize,
+        columns: ColumnSet,
+        column_widths: Vec<usize>,
+        bytes_format: BytesFormat,
+        extras: TreeExtras<'a>,
+    ) -> Self {
+        let TreeExtras { baselines, has_relative, relative_to_fastest, trend, histogram } = extras;
+
+        // Relative values are at most as wide as "1.00x slower".
+        let mut extra_widths = Vec::new();
+        if has_relative {
+            extra_widths.push(12);
+        }
+        if relative_to_fastest {
+            extra_widths.push("vs fastest".len());
+        }
+        if let Some((_, runs)) = trend {
+            // The last column doesn't use padding.
+            extra_widths.push(if baselines.is_empty() { 0 } else { runs.max("trend".len()) });
+        }
+        for (i, baseline) in baselines.iter().enumerate() {
+            // The last column doesn't use padding. Changes are usually as wide
+            // as "+1.23% median, +1.23% mean (no change)".
+            let is_last = i == baselines.len() - 1;
+            extra_widths.push(if is_last { 0 } else { baseline.name.len().max(38) });
+        }
+
         Self {
             max_name_span,
+            columns,
             column_widths,
             depth: 0,
             current_prefix: String::new(),
             write_buf: String::new(),
+            is_last_leaf: false,
+            bytes_format,
+            baselines,
+            has_relative,
+            trend,
+            extra_widths,
+            leaf_path: String::new(),
+            leaf_baseline_key: String::new(),
+            leaf_baseline_median: None,
+            relative_to_fastest,
+            pending_leaves: vec![Vec::new()],
+            histogram,
+        }
+    }
+
+    fn has_columns(&self) -> bool {
+        !self.column_widths.is_empty()
+    }
+
+    /// Returns the values of the extra columns after the table.
+    fn extra_columns<'s>(
+        &self,
+        relative: &'s str,
+        vs_fastest: &'s str,
+        trend: &'s str,
+        baselines: &[&'s str],
+    ) -> Vec<&'s str> {
+        let mut columns = Vec::new();
+        if self.has_relative {
+            columns.push(relative);
+        }
+        if self.relative_to_fastest {
+            columns.push(vs_fastest);
+        }
+        if self.trend.is_some() {
+            columns.push(trend);
+        }
+        columns.extend_from_slice(baselines);
+        columns
+    }
+
+    /// Returns empty values for the extra columns after the table.
+    fn extra_spacers(&self) -> Vec<&'static str> {
+        vec![""; self.extra_widths.len()]
+    }
+
+    /// Formats how many times faster or slower `stats` is than the current
+    /// leaf's `baseline` benchmark.
+    fn relative(&self, stats: &Stats) -> String {
+        let Some(baseline_median) = self.leaf_baseline_median else {
+            return String::new();
+        };
+
+        let median = stats.time_nanos().median;
+        if median <= 0.0 || baseline_median <= 0.0 {
+            return String::new();
+        }
+
+        let ratio = baseline_median / median;
+        if ratio >= 1.0 {
+            format!("{} faster", format_ratio(ratio))
+        } else {
+            format!("{} slower", format_ratio(ratio.recip()))
+        }
+    }
+
+    /// Draws a sparkline of the current leaf's median times in previous runs
+    /// followed by `stats`.
+    fn trend(&self, stats: &Stats) -> String {
+        let Some((history, runs)) = self.trend else {
+            return String::new();
+        };
+
+        let mut medians: Vec<f64> = history.medians(&self.leaf_path).collect();
+        medians.push(stats.time_nanos().median);
+
+        sparkline(&medians[medians.len().saturating_sub(runs)..])
+    }
+
+    /// Buffers `lines` for `relative_to_fastest` alongside the current
+    /// parent's leaves.
+    fn push_pending_lines(&mut self, lines: String) {
+        if let Some(pending_leaves) = self.pending_leaves.last_mut() {
+            pending_leaves.push(PendingLeaf::Lines(lines));
         }
     }
+
+    /// Formats the output buffered for `relative_to_fastest` at the current
+    /// depth, now that all siblings have stats.
+    fn write_pending_leaves(&mut self, out: &mut String) {
+        let pending_leaves = self.pending_leaves.pop().unwrap_or_default();
+
+        let fastest_median = pending_leaves
+            .iter()
+            .filter_map(|leaf| match leaf {
+                PendingLeaf::Stats { stats, .. } => Some(stats.time_nanos().median),
+                PendingLeaf::Lines(_) => None,
+            })
+            .filter(|&median| median > 0.0)
+            .min_by(f64::total_cmp);
+
+        for leaf in pending_leaves {
+            match leaf {
+                PendingLeaf::Lines(lines) => out.push_str(&lines),
+                PendingLeaf::Stats {
+                    name_line,
+                    is_last,
+                    path,
+                    baseline_key,
+                    baseline_median,
+                    stats,
+                } => {
+                    self.is_last_leaf = is_last;
+                    self.leaf_path = path;
+                    self.leaf_baseline_key = baseline_key;
+                    self.leaf_baseline_median = baseline_median;
+
+                    let vs_fastest = match fastest_median {
+                        Some(fastest_median) => {
+                            format_ratio(stats.time_nanos().median / fastest_median)
+                        }
+                        None => String::new(),
+                    };
+
+                    out.push_str(&name_line);
+                    self.write_stats(&stats, &vs_fastest, out);
+                }
+            }
+        }
+    }
+
+    /// Formats the change from `baseline` to `stats`.
+    fn baseline_change(&self, baseline: &Baseline, stats: &Stats) -> String {
+        let Some(old) = baseline.get(&self.leaf_baseline_key) else {
+            return "(new)".to_owned();
+        };
+
+        let time = stats.time_nanos();
+        let samples: Vec<f64> = stats.sample_nanos().collect();
+        let new = RunTimes { median: time.median, mean: time.mean, samples: &samples };
+
+        let Comparison { median_change, mean_change, verdict } = Comparison::new(&old, &new);
+
+        let mut change =
+            format!("{:+.2}% median, {:+.2}% mean", median_change * 100.0, mean_change * 100.0);
+
+        // Significance is unknown without samples, such as for results from
+        // `--format=json`.
+        if !old.samples.is_empty() && !new.samples.is_empty() {
+            change = format!("{change} ({})", verdict.name());
+        }
+
+        change
+    }
 }
 
-impl TreePainter {
-    /// Enter a parent node.
-    pub fn start_parent(&mut self, name: &str, is_last: bool) {
+impl Reporter for TreePainter<'_> {
+    fn start_parent(&mut self, node: &Node) {
+        let Node { name, is_last, .. } = *node;
         let is_top_level = self.depth == 0;
         let has_columns = self.has_columns();
 
+        let baseline_names: Vec<&str> = self.baselines.iter().map(|b| b.name.as_str()).collect();
+        let extra_names = self.extra_columns("relative", "vs fastest", "trend", &baseline_names);
+        let extra_spacers = self.extra_spacers();
+
         let buf = &mut self.write_buf;
         buf.clear();
 
@@ -74,16 +348,27 @@ impl TreePainter {
 
         // Write column headings.
         if has_columns && is_top_level {
-            let names = TreeColumnData::from_fn(TreeColumn::name);
-            names.write(buf, &mut self.column_widths);
+            let names = TreeColumnData::from_fn(&self.columns.columns, TreeColumn::name);
+            names.write(buf, &mut self.column_widths, &extra_names, &mut self.extra_widths);
         }
 
         // Write column spacers.
         if has_columns && !is_top_level {
-            TreeColumnData([""; TreeColumn::COUNT]).write(buf, &mut self.column_widths);
+            TreeColumnData(vec![""; self.columns.columns.len()]).write(
+                buf,
+                &mut self.column_widths,
+                &extra_spacers,
+                &mut self.extra_widths,
+            );
         }
 
-        println!("{buf}");
+        if self.relative_to_fastest {
+            let line = format!("{buf}\n");
+            self.push_pending_lines(line);
+            self.pending_leaves.push(Vec::new());
+        } else {
+            println!("{buf}");
+        }
 
         self.depth += 1;
 
@@ -92,13 +377,31 @@ impl TreePainter {
         }
     }
 
-    /// Exit the current parent node.
-    pub fn finish_parent(&mut self) {
+    fn finish_parent(&mut self) {
         self.depth -= 1;
 
         // Improve legibility for multiple top-level parents.
-        if self.depth == 0 {
-            println!();
+        let separator = if self.depth == 0 { "\n" } else { "" };
+
+        if self.relative_to_fastest {
+            // The parent's output is complete, but may still be followed by
+            // siblings that affect the leaves before it.
+            let mut out = String::new();
+            self.write_pending_leaves(&mut out);
+            out.push_str(separator);
+            self.push_pending_lines(out);
+
+            // Print once no top-level leaf is waiting on its siblings.
+            let is_complete = self.pending_leaves.len() == 1
+                && self.pending_leaves[0].iter().all(|leaf| matches!(leaf, PendingLeaf::Lines(_)));
+            if is_complete {
+                let mut out = String::new();
+                self.write_pending_leaves(&mut out);
+                self.pending_leaves.push(Vec::new());
+                print!("{out}");
+            }
+        } else {
+            print!("{separator}");
         }
 
         // The prefix is extended by 3 `char`s at a time.
@@ -110,11 +413,10 @@ impl TreePainter {
         self.current_prefix.truncate(new_prefix_len);
     }
 
-    /// Indicate that the next child node was ignored.
-    ///
-    /// This semantically combines start/finish operations.
-    pub fn ignore_leaf(&mut self, name: &str, is_last: bool) {
+    fn ignore_leaf(&mut self, node: &Node) {
+        let Node { name, is_last, .. } = *node;
         let has_columns = self.has_columns();
+        let extra_spacers = self.extra_spacers();
 
         let buf = &mut self.write_buf;
         buf.clear();
@@ -135,17 +437,31 @@ impl TreePainter {
         }
 
         if has_columns {
-            TreeColumnData::from_first("(ignored)").write(buf, &mut self.column_widths);
+            TreeColumnData::from_first(self.columns.columns.len(), "(ignored)").write(
+                buf,
+                &mut self.column_widths,
+                &extra_spacers,
+                &mut self.extra_widths,
+            );
         } else {
             buf.push_str("(ignored)");
         }
 
-        println!("{buf}");
+        if self.relative_to_fastest {
+            let line = format!("{buf}\n");
+            self.push_pending_lines(line);
+        } else {
+            println!("{buf}");
+        }
     }
 
-    /// Enter a leaf node.
-    pub fn start_leaf(&mut self, name: &str, is_last: bool) {
+    fn start_leaf(&mut self, node: &Node) {
+        let Node { name, path, is_last, baseline, .. } = *node;
         let has_columns = self.has_columns();
+        self.is_last_leaf = is_last;
+        path.clone_into(&mut self.leaf_path);
+        self.leaf_baseline_key = node.baseline_key().into_owned();
+        self.leaf_baseline_median = baseline.map(|stats| stats.time_nanos().median);
 
         let buf = &mut self.write_buf;
         buf.clear();
@@ -165,17 +481,64 @@ impl TreePainter {
             }
         }
 
-        print!("{buf}");
-        _ = std::io::stdout().flush();
+        // Buffered leaves are printed once all siblings have stats.
+        if !self.relative_to_fastest {
+            print!("{buf}");
+            _ = std::io::stdout().flush();
+        }
     }
 
-    /// Exit the current leaf node.
-    pub fn finish_empty_leaf(&mut self) {
-        println!();
+    fn finish_empty_leaf(&mut self) {
+        if self.relative_to_fastest {
+            let line = format!("{}\n", self.write_buf);
+            self.push_pending_lines(line);
+        } else {
+            println!();
+        }
+    }
+
+    fn finish_leaf(&mut self, stats: &Stats) {
+        if self.relative_to_fastest {
+            let leaf = PendingLeaf::Stats {
+                name_line: self.write_buf.clone(),
+                is_last: self.is_last_leaf,
+                path: self.leaf_path.clone(),
+                baseline_key: self.leaf_baseline_key.clone(),
+                baseline_median: self.leaf_baseline_median,
+                stats: Box::new(stats.clone()),
+            };
+            if let Some(pending_leaves) = self.pending_leaves.last_mut() {
+                pending_leaves.push(leaf);
+            }
+        } else {
+            let mut out = String::new();
+            self.write_stats(stats, "", &mut out);
+            print!("{out}");
+        }
     }
 
-    /// Exit the current leaf node, emitting statistics.
-    pub fn finish_leaf(&mut self, is_last: bool, stats: &Stats, bytes_format: BytesFormat) {
+    fn finish(&mut self) {
+        let mut out = String::new();
+        self.write_pending_leaves(&mut out);
+        print!("{out}");
+    }
+}
+
+impl TreePainter<'_> {
+    /// Writes the stats of the current leaf after its name.
+    fn write_stats(&mut self, stats: &Stats, vs_fastest: &str, out: &mut String) {
+        let is_last = self.is_last_leaf;
+        let bytes_format = self.bytes_format;
+        let columns = &self.columns.columns;
+
+        let relative = self.relative(stats);
+        let trend = self.trend(stats);
+        let baseline_changes: Vec<String> =
+            self.baselines.iter().map(|baseline| self.baseline_change(baseline, stats)).collect();
+        let baseline_changes: Vec<&str> = baseline_changes.iter().map(String::as_str).collect();
+        let extra_values = self.extra_columns(&relative, vs_fastest, &trend, &baseline_changes);
+        let extra_spacers = self.extra_spacers();
+
         let buf = &mut self.write_buf;
         buf.clear();
 
@@ -183,91 +546,91 @@ impl TreePainter {
         let serialized_alloc_tallies = AllocOp::ALL.map(|op| {
             let tally = stats.alloc_tallies.get(op);
 
-            if tally.is_zero() {
+            if !self.columns.show_alloc || tally.is_zero() {
                 return None;
             }
 
-            let column_tallies = TreeColumn::ALL.map(|column| {
-                let prefix = if column.is_first() { "  " } else { "" };
+            let column_tallies: Vec<_> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| {
+                    let prefix = if i == 0 { "  " } else { "" };
 
-                let tally = AllocTally {
-                    count: column.get_stat(&tally.count).copied()?,
-                    size: column.get_stat(&tally.size).copied()?,
-                };
+                    let tally = AllocTally {
+                        count: column.get_stat(&tally.count).copied()?,
+                        size: column.get_stat(&tally.size).copied()?,
+                    };
 
-                Some((prefix, tally))
-            });
+                    Some((prefix, tally))
+                })
+                .collect();
 
             Some(AllocTally {
-                count: column_tallies.map(|tally| {
-                    if let Some((prefix, tally)) = tally {
-                        format!("{prefix}{}", util::fmt::format_f64(tally.count, 4))
-                    } else {
-                        String::new()
-                    }
-                }),
-                size: column_tallies.map(|tally| {
-                    if let Some((prefix, tally)) = tally {
-                        format!("{prefix}{}", util::fmt::format_bytes(tally.size, 4, bytes_format))
-                    } else {
-                        String::new()
-                    }
-                }),
+                count: column_tallies
+                    .iter()
+                    .map(|tally| {
+                        if let Some((prefix, tally)) = tally {
+                            format!("{prefix}{}", util::fmt::format_f64(tally.count, 4))
+                        } else {
+                            String::new()
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+                size: column_tallies
+                    .iter()
+                    .map(|tally| {
+                        if let Some((prefix, tally)) = tally {
+                            format!(
+                                "{prefix}{}",
+                                util::fmt::format_bytes(tally.size, 4, bytes_format)
+                            )
+                        } else {
+                            String::new()
+                        }
+                    })
+                    .collect::<Vec<_>>(),
             })
         });
 
         // Serialize counter stats early so we can resize columns early.
+        let show_throughput = self.columns.show_throughput;
         let serialized_counters = KnownCounterKind::ALL.map(|counter_kind| {
-            let counter_stats = stats.get_counts(counter_kind);
-
-            TreeColumn::ALL
-                .map(|column| -> Option<String> {
-                    let count = *column.get_stat(counter_stats?)?;
-                    let time = *column.get_stat(&stats.time)?;
-
-                    Some(
-                        AnyCounter::known(counter_kind, count)
-                            .display_throughput(time, bytes_format)
-                            .to_string(),
-                    )
+            columns
+                .iter()
+                .map(|column| {
+                    column
+                        .get_throughput(stats, counter_kind, bytes_format)
+                        .filter(|_| show_throughput)
                 })
                 .map(Option::unwrap_or_default)
+                .collect::<Vec<_>>()
         });
 
-        for column in TreeColumn::time_stats() {
-            let width = &mut self.column_widths[column as usize];
-
+        for (i, width) in self.column_widths.iter_mut().enumerate() {
             for counter in &serialized_counters {
-                let s = &counter[column as usize];
-                *width = (*width).max(s.chars().count());
+                *width = (*width).max(counter[i].chars().count());
             }
 
             for s in serialized_alloc_tallies
                 .iter()
                 .flatten()
                 .flat_map(AllocTally::as_array)
-                .map(|values| &values[column as usize])
+                .map(|values| &values[i])
             {
                 *width = (*width).max(s.chars().count());
             }
         }
 
         // Write time stats with iter and sample counts.
-        TreeColumnData::from_fn(|column| -> String {
-            let stat: &dyn ToString = match column {
-                TreeColumn::Fastest => &stats.time.fastest,
-                TreeColumn::Slowest => &stats.time.slowest,
-                TreeColumn::Median => &stats.time.median,
-                TreeColumn::Mean => &stats.time.mean,
-                TreeColumn::Samples => &stats.sample_count,
-                TreeColumn::Iters => &stats.iter_count,
-            };
-            stat.to_string()
-        })
-        .as_ref::<str>()
-        .write(buf, &mut self.column_widths);
+        TreeColumnData::from_fn(columns, |column| column.get_cell(stats)).as_ref::<str>().write(
+            buf,
+            &mut self.column_widths,
+            &extra_values,
+            &mut self.extra_widths,
+        );
 
-        println!("{buf}");
+        out.push_str(buf);
+        out.push('\n');
 
         // Write counter stats.
         let counter_stats = serialized_counters.map(TreeColumnData);
@@ -298,8 +661,14 @@ impl TreePainter {
                 }
             };
 
-            counter_stats.write(buf, &mut self.column_widths);
-            println!("{buf}");
+            counter_stats.write(
+                buf,
+                &mut self.column_widths,
+                &extra_spacers,
+                &mut self.extra_widths,
+            );
+            out.push_str(buf);
+            out.push('\n');
         }
 
         // Write allocation information.
@@ -327,8 +696,14 @@ impl TreePainter {
                 }
             };
 
-            TreeColumnData::from_first(op.prefix()).write(buf, &mut self.column_widths);
-            println!("{buf}");
+            TreeColumnData::from_first(columns.len(), op.prefix()).write(
+                buf,
+                &mut self.column_widths,
+                &extra_spacers,
+                &mut self.extra_widths,
+            );
+            out.push_str(buf);
+            out.push('\n');
 
             for value in tallies.as_array() {
                 buf.clear();
@@ -350,16 +725,82 @@ impl TreePainter {
                     }
                 };
 
-                TreeColumnData::from_fn(|column| value[column as usize].as_str())
-                    .write(buf, &mut self.column_widths);
+                TreeColumnData(value.iter().map(String::as_str).collect()).write(
+                    buf,
+                    &mut self.column_widths,
+                    &extra_spacers,
+                    &mut self.extra_widths,
+                );
+
+                out.push_str(buf);
+                out.push('\n');
+            }
+        }
+
+        // Write the distribution of sample times, aligned with the table.
+        if self.histogram && !stats.samples.is_empty() {
+            buf.clear();
+            buf.push_str(&self.current_prefix);
 
-                println!("{buf}");
+            if !is_last {
+                buf.push('│');
             }
+
+            let buf_len = buf.chars().count();
+            let pad_len = TREE_COL_BUF + self.max_name_span.saturating_sub(buf_len);
+            buf.push_str(&" ".repeat(pad_len));
+
+            buf.push_str(&histogram(&stats.samples, HISTOGRAM_BINS));
+            out.push_str(buf.trim_end());
+            out.push('\n');
         }
     }
+}
 
-    fn has_columns(&self) -> bool {
-        !self.column_widths.iter().all(|&w| w == 0)
+/// Draws the distribution of `samples` as block characters, one per bin of
+/// equal width between the fastest and slowest sample.
+///
+/// Bar heights are relative to the fullest bin, and empty bins are blank.
+fn histogram(samples: &[FineDuration], bins: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let (Some(&min), Some(&max)) = (samples.iter().min(), samples.iter().max()) else {
+        return String::new();
+    };
+    let (min, range) = (min.as_nanos_f64(), max.as_nanos_f64() - min.as_nanos_f64());
+
+    let mut counts = vec![0usize; bins];
+    for sample in samples {
+        let index = if range > 0.0 {
+            ((sample.as_nanos_f64() - min) / range * bins as f64) as usize
+        } else {
+            0
+        };
+        counts[index.min(bins - 1)] += 1;
+    }
+
+    let max_count = counts.iter().copied().max().unwrap_or_default();
+
+    counts
+        .iter()
+        .map(|&count| {
+            if count == 0 {
+                return ' ';
+            }
+
+            let height = (count as f64 / max_count as f64 * BARS.len() as f64).ceil() as usize;
+            BARS[height.clamp(1, BARS.len()) - 1]
+        })
+        .collect()
+}
+
+/// Formats a ratio of times, such as `1.80x`.
+fn format_ratio(ratio: f64) -> String {
+    // Large ratios do not benefit from decimal places.
+    if ratio < 100.0 {
+        format!("{ratio:.2}x")
+    } else {
+        format!("{ratio:.0}x")
     }
 }
 
@@ -372,35 +813,72 @@ pub(crate) enum TreeColumn {
     Mean,
     Samples,
     Iters,
+    P5,
+    P25,
+    P75,
+    P95,
+    P99,
+    StdDev,
+    Mad,
+    MedianCi,
+    MeanCi,
+    Outliers,
+    Slope,
+    RSquared,
 }
 
 impl TreeColumn {
-    pub const COUNT: usize = 6;
+    pub const COUNT: usize = 18;
 
     pub const ALL: [Self; Self::COUNT] = {
+        use TreeColumn::*;
+        [
+            Fastest, Slowest, Median, Mean, Samples, Iters, P5, P25, P75, P95, P99, StdDev, Mad,
+            MedianCi, MeanCi, Outliers, Slope, RSquared,
+        ]
+    };
+
+    /// Columns shown in human-readable output unless `--columns` is used.
+    pub const DEFAULT: [Self; 6] = {
         use TreeColumn::*;
         [Fastest, Slowest, Median, Mean, Samples, Iters]
     };
 
+    /// Columns with a value in [`StatsSet`], which are also used for
+    /// throughput and allocation stats.
     #[inline]
     pub fn time_stats() -> impl Iterator<Item = Self> {
         use TreeColumn::*;
         [Fastest, Slowest, Median, Mean].into_iter()
     }
 
-    #[inline]
-    pub fn is_first(self) -> bool {
-        let [first, ..] = Self::ALL;
-        self == first
-    }
-
-    #[inline]
-    pub fn is_last(self) -> bool {
-        let [.., last] = Self::ALL;
-        self == last
+    /// Returns the table column for `column`, or [`None`] if it is shown
+    /// outside of the table.
+    pub fn from_column(column: Column) -> Option<Self> {
+        Some(match column {
+            Column::Fastest => Self::Fastest,
+            Column::Slowest => Self::Slowest,
+            Column::Median => Self::Median,
+            Column::Mean => Self::Mean,
+            Column::Samples => Self::Samples,
+            Column::Iters => Self::Iters,
+            Column::P5 => Self::P5,
+            Column::P25 => Self::P25,
+            Column::P75 => Self::P75,
+            Column::P95 => Self::P95,
+            Column::P99 => Self::P99,
+            Column::StdDev => Self::StdDev,
+            Column::Mad => Self::Mad,
+            Column::MedianCi => Self::MedianCi,
+            Column::MeanCi => Self::MeanCi,
+            Column::Outliers => Self::Outliers,
+            Column::Slope => Self::Slope,
+            Column::RSquared => Self::RSquared,
+            Column::Alloc | Column::Throughput => return None,
+        })
     }
 
-    fn name(self) -> &'static str {
+    pub fn name(self) -> &'static str {
         match self {
             Self::Fastest => "fastest",
             Self::Slowest => "slowest",
@@ -408,56 +886,244 @@ impl TreeColumn {
             Self::Mean => "mean",
             Self::Samples => "samples",
             Self::Iters => "iters",
+            Self::P5 => "p5",
+            Self::P25 => "p25",
+            Self::P75 => "p75",
+            Self::P95 => "p95",
+            Self::P99 => "p99",
+            Self::StdDev => "std_dev",
+            Self::Mad => "mad",
+            Self::MedianCi => "median_ci",
+            Self::MeanCi => "mean_ci",
+            Self::Outliers => "outliers",
+            Self::Slope => "slope",
+            Self::RSquared => "r2",
         }
     }
 
+    /// Returns `true` for columns of iteration times, including percentiles
+    /// and dispersion.
     #[inline]
     pub fn is_time_stat(self) -> bool {
         use TreeColumn::*;
-        matches!(self, Fastest | Slowest | Median | Mean)
+        !matches!(self, Samples | Iters | MedianCi | MeanCi | Outliers | RSquared)
+    }
+
+    /// Returns `true` for columns of confidence interval margins.
+    #[inline]
+    pub fn is_ci(self) -> bool {
+        matches!(self, Self::MedianCi | Self::MeanCi)
     }
 
     #[inline]
-    fn get_stat<T>(self, stats: &StatsSet<T>) -> Option<&T> {
+    pub fn get_stat<T>(self, stats: &StatsSet<T>) -> Option<&T> {
         match self {
             Self::Fastest => Some(&stats.fastest),
             Self::Slowest => Some(&stats.slowest),
             Self::Median => Some(&stats.median),
             Self::Mean => Some(&stats.mean),
-            Self::Samples | Self::Iters => None,
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn get_distribution<T>(self, distribution: &Distribution<T>) -> Option<&T> {
+        match self {
+            Self::P5 => Some(&distribution.p5),
+            Self::P25 => Some(&distribution.p25),
+            Self::P75 => Some(&distribution.p75),
+            Self::P95 => Some(&distribution.p95),
+            Self::P99 => Some(&distribution.p99),
+            Self::StdDev => Some(&distribution.std_dev),
+            Self::Mad => Some(&distribution.mad),
+            _ => None,
+        }
+    }
+
+    /// Returns the iteration time of this column in `stats`.
+    #[inline]
+    pub fn get_time(self, stats: &Stats) -> Option<FineDuration> {
+        self.get_stat(&stats.time)
+            .or_else(|| self.get_distribution(&stats.time_distribution))
+            .copied()
+            .or_else(|| match self {
+                Self::Slope => stats.regression.map(|r| FineDuration::from_nanos_f64(r.slope)),
+                _ => None,
+            })
+    }
+
+    /// Returns the value of a column that is not an iteration time, which is
+    /// used for sorting.
+    pub fn get_value(self, stats: &Stats) -> Option<f64> {
+        match self {
+            Self::Samples => Some(stats.sample_count.into()),
+            Self::Iters => Some(stats.iter_count as f64),
+            Self::MedianCi | Self::MeanCi => self.get_ci_margin(stats),
+            Self::Outliers => Some(stats.outliers.total().into()),
+            Self::RSquared => stats.regression.map(|regression| regression.r_squared),
+            _ => None,
+        }
+    }
+
+    /// Formats the value of a column that is not an iteration time, or returns
+    /// [`None`] for iteration time columns.
+    ///
+    /// Values missing from `stats` are formatted as empty.
+    pub fn get_text(self, stats: &Stats) -> Option<String> {
+        if self.is_time_stat() {
+            return None;
+        }
+
+        Some(match self {
+            Self::Samples => stats.sample_count.to_string(),
+            Self::Iters => stats.iter_count.to_string(),
+            Self::MedianCi | Self::MeanCi => {
+                self.get_ci_margin(stats).map(format_margin).unwrap_or_default()
+            }
+            Self::Outliers => stats.outliers.summary(),
+            _ => self.get_value(stats).map(|value| format!("{value:.4}")).unwrap_or_default(),
+        })
+    }
+
+    /// Formats the value of this column in `stats` as shown in its table cell.
+    pub fn get_cell(self, stats: &Stats) -> String {
+        self.get_text(stats).unwrap_or_else(|| {
+            self.get_time(stats).map(|time| time.to_string()).unwrap_or_default()
+        })
+    }
+
+    /// Formats the throughput of `counter_kind` in this column of `stats`.
+    pub fn get_throughput(
+        self,
+        stats: &Stats,
+        counter_kind: KnownCounterKind,
+        bytes_format: BytesFormat,
+    ) -> Option<String> {
+        let count = *self.get_stat(stats.get_counts(counter_kind)?)?;
+        let time = *self.get_stat(&stats.time)?;
+
+        Some(
+            AnyCounter::known(counter_kind, count)
+                .display_throughput(time, bytes_format)
+                .to_string(),
+        )
+    }
+
+    /// Returns the number of characters needed to show this column of `stats`,
+    /// including throughput below its time.
+    pub fn get_width(self, stats: &Stats, bytes_format: BytesFormat) -> usize {
+        KnownCounterKind::ALL
+            .into_iter()
+            .filter_map(|counter_kind| self.get_throughput(stats, counter_kind, bytes_format))
+            .chain([self.get_cell(stats)])
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Returns the confidence interval of this column in `stats`.
+    #[inline]
+    pub fn get_ci(self, stats: &Stats) -> Option<ConfidenceInterval<FineDuration>> {
+        match self {
+            Self::MedianCi => stats.median_ci,
+            Self::MeanCi => stats.mean_ci,
+            _ => None,
         }
     }
+
+    /// Returns the confidence interval margin of this column in `stats` as a
+    /// fraction of its estimate.
+    pub fn get_ci_margin(self, stats: &Stats) -> Option<f64> {
+        let estimate = match self {
+            Self::MedianCi => stats.time.median,
+            Self::MeanCi => stats.time.mean,
+            _ => return None,
+        };
+
+        self.get_ci(stats)?.map(|time| time.as_nanos_f64()).relative_margin(estimate.as_nanos_f64())
+    }
 }
 
-#[derive(Default)]
-struct TreeColumnData<T>([T; TreeColumn::COUNT]);
+/// Formats a confidence interval margin fraction like `± 1.23%`.
+pub(crate) fn format_margin(margin: f64) -> String {
+    format!("± {:.2}%", margin * 100.0)
+}
+
+/// Columns selected for human-readable output.
+#[derive(Clone)]
+pub(crate) struct ColumnSet {
+    /// Columns of the table, in the order they are shown.
+    pub columns: Vec<TreeColumn>,
+
+    /// Whether to show allocation stats below each benchmark's time stats.
+    pub show_alloc: bool,
+
+    /// Whether to show counter throughput below each benchmark's time stats.
+    pub show_throughput: bool,
+}
+
+impl Default for ColumnSet {
+    fn default() -> Self {
+        Self { columns: TreeColumn::DEFAULT.to_vec(), show_alloc: true, show_throughput: true }
+    }
+}
+
+impl ColumnSet {
+    pub fn new(columns: &[Column]) -> Self {
+        Self {
+            columns: columns.iter().copied().filter_map(TreeColumn::from_column).collect(),
+            show_alloc: columns.contains(&Column::Alloc),
+            show_throughput: columns.contains(&Column::Throughput),
+        }
+    }
+
+    /// Returns an empty set, for when there are no stats to show.
+    pub fn empty() -> Self {
+        Self { columns: Vec::new(), show_alloc: false, show_throughput: false }
+    }
+}
+
+struct TreeColumnData<T>(Vec<T>);
 
 impl<T> TreeColumnData<T> {
     #[inline]
-    fn from_first(value: T) -> Self
+    fn from_first(count: usize, value: T) -> Self
     where
-        Self: Default,
+        T: Default,
     {
-        let mut data = Self::default();
-        data.0[0] = value;
-        data
+        let mut data: Vec<T> = (0..count).map(|_| T::default()).collect();
+        if let Some(first) = data.first_mut() {
+            *first = value;
+        }
+        Self(data)
     }
 
     #[inline]
-    fn from_fn<F>(f: F) -> Self
+    fn from_fn<F>(columns: &[TreeColumn], f: F) -> Self
     where
         F: FnMut(TreeColumn) -> T,
     {
-        Self(TreeColumn::ALL.map(f))
+        Self(columns.iter().copied().map(f).collect())
     }
 }
 
 impl TreeColumnData<&str> {
     /// Writes the column data into the buffer.
-    fn write(&self, buf: &mut String, column_widths: &mut [usize; TreeColumn::COUNT]) {
-        for (column, value) in self.0.iter().enumerate() {
+    ///
+    /// Values in `extra` are written as extra columns after the last one,
+    /// which then gets padded like the others.
+    fn write(
+        &self,
+        buf: &mut String,
+        column_widths: &mut [usize],
+        extra: &[&str],
+        extra_widths: &mut [usize],
+    ) {
+        let column_count = self.0.len() + extra.len();
+
+        for (column, value) in self.0.iter().chain(extra).enumerate() {
             let is_first = column == 0;
-            let is_last = column == TreeColumn::COUNT - 1;
+            let is_last = column == column_count - 1;
 
             let value_width = value.chars().count();
 
@@ -477,10 +1143,15 @@ impl TreeColumnData<&str> {
 
             // Right-pad remaining width or update column width to new maximum.
             if !is_last {
-                if let Some(rem_width) = column_widths[column].checked_sub(value_width) {
+                let column_width = match column.checked_sub(self.0.len()) {
+                    None => &mut column_widths[column],
+                    Some(extra_column) => &mut extra_widths[extra_column],
+                };
+
+                if let Some(rem_width) = column_width.checked_sub(value_width) {
                     buf.extend(repeat(' ').take(rem_width));
                 } else {
-                    column_widths[column] = value_width;
+                    *column_width = value_width;
                 }
             }
         }
@@ -493,6 +1164,89 @@ impl<T> TreeColumnData<T> {
     where
         T: AsRef<U>,
     {
-        TreeColumnData::from_fn(|column| self.0[column as usize].as_ref())
+        TreeColumnData(self.0.iter().map(AsRef::as_ref).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NodeKind;
+
+    #[test]
+    fn histogram() {
+        let nanos = |values: &[f64]| -> Vec<FineDuration> {
+            values.iter().map(|&nanos| FineDuration::from_nanos_f64(nanos)).collect()
+        };
+
+        assert_eq!(super::histogram(&[], 4), "");
+        assert_eq!(super::histogram(&nanos(&[5.0, 5.0]), 4), "█   ");
+        assert_eq!(super::histogram(&nanos(&[0.0, 0.0, 0.0, 0.0, 1.0, 4.0]), 4), "█▂ ▂");
+    }
+
+    #[test]
+    fn column_set() {
+        let columns = ColumnSet::new(&[Column::Throughput, Column::P99, Column::Median]);
+        assert!(columns.columns == [TreeColumn::P99, TreeColumn::Median]);
+        assert!(columns.show_throughput);
+        assert!(!columns.show_alloc);
+
+        let columns = ColumnSet::new(&[Column::Median, Column::Alloc]);
+        assert!(!columns.show_throughput);
+        assert!(columns.show_alloc);
+    }
+
+    #[test]
+    fn width() {
+        let stats = &Stats { sample_count: 100, ..Stats::test(1.0, 1.0) }
+            .with_count(KnownCounterKind::Items, 1000);
+
+        let bytes_format = BytesFormat::default();
+
+        // Throughput is wider than the time above it.
+        assert_eq!(TreeColumn::Median.get_cell(stats), "1 ns");
+        assert_eq!(TreeColumn::Median.get_width(stats, bytes_format), "1 Titem/s".len());
+
+        assert_eq!(TreeColumn::Samples.get_width(stats, bytes_format), 3);
+    }
+
+    #[test]
+    fn relative_to_fastest() {
+        let node = |name, kind, is_last| Node {
+            name,
+            path: name,
+            kind,
+            is_last,
+            baseline: None,
+            thread_count: None,
+        };
+
+        let mut painter = TreePainter::new(
+            0,
+            ColumnSet::default(),
+            vec![0; TreeColumn::DEFAULT.len()],
+            BytesFormat::default(),
+            TreeExtras { relative_to_fastest: true, ..TreeExtras::default() },
+        );
+
+        // The fastest leaf comes after a sibling group, which must not cause
+        // the leaf before it to be compared only against earlier siblings.
+        painter.start_parent(&node("math", NodeKind::Group, true));
+        painter.start_leaf(&node("slow", NodeKind::Bench, false));
+        painter.finish_leaf(&Stats::test(10.0, 10.0));
+        painter.start_parent(&node("group", NodeKind::Group, false));
+        painter.start_leaf(&node("empty", NodeKind::Bench, true));
+        painter.finish_empty_leaf();
+        painter.finish_parent();
+        painter.start_leaf(&node("fast", NodeKind::Bench, true));
+        painter.finish_leaf(&Stats::test(1.0, 1.0));
+
+        let mut out = String::new();
+        painter.write_pending_leaves(&mut out);
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert!(lines[0].contains("slow") && lines[0].ends_with("10.00x"), "{out}");
+        assert!(lines[1].contains("group") && lines[2].contains("empty"), "{out}");
+        assert!(lines[3].contains("fast") && lines[3].ends_with("1.00x"), "{out}");
     }
 }
@@ -0,0 +1,90 @@
+//! Reading repository state without depending on the `git` executable.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Returns the commit hash of `HEAD` for the repository containing the current
+/// directory.
+pub(crate) fn head_commit() -> Option<String> {
+    let current_dir = std::env::current_dir().ok()?;
+    let git_dir = current_dir.ancestors().find_map(find_git_dir)?;
+    read_head(&git_dir)
+}
+
+/// Returns the `.git` directory within `dir`.
+///
+/// For worktrees and submodules, `.git` is a file containing
+/// `gitdir: <path>`.
+fn find_git_dir(dir: &Path) -> Option<PathBuf> {
+    let dot_git = dir.join(".git");
+
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+
+    let contents = fs::read_to_string(&dot_git).ok()?;
+    let git_dir = contents.strip_prefix("gitdir:")?.trim();
+    Some(dir.join(git_dir))
+}
+
+/// Resolves `HEAD` in `git_dir` to a commit hash.
+fn read_head(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+
+    // Detached `HEAD` is the commit hash itself.
+    let Some(ref_name) = head.strip_prefix("ref:") else {
+        return Some(head.to_owned());
+    };
+    let ref_name = ref_name.trim();
+
+    // Worktrees share refs with the main repository's directory.
+    let common_dir = fs::read_to_string(git_dir.join("commondir"))
+        .map(|common_dir| git_dir.join(common_dir.trim()))
+        .unwrap_or_else(|_| git_dir.to_owned());
+
+    for dir in [git_dir, &common_dir] {
+        if let Ok(commit) = fs::read_to_string(dir.join(ref_name)) {
+            return Some(commit.trim().to_owned());
+        }
+    }
+
+    // Refs may instead be packed as `<hash> <ref>` lines.
+    let packed_refs = fs::read_to_string(common_dir.join("packed-refs")).ok()?;
+    packed_refs.lines().find_map(|line| {
+        let (commit, name) = line.split_once(' ')?;
+        (name == ref_name).then(|| commit.to_owned())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_head() {
+        let git_dir = std::env::temp_dir().join(format!("divan-git-{}", std::process::id()));
+        let commit = "0123456789abcdef0123456789abcdef01234567";
+
+        fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+
+        // Detached.
+        fs::write(git_dir.join("HEAD"), format!("{commit}\n")).unwrap();
+        assert_eq!(super::read_head(&git_dir).as_deref(), Some(commit));
+
+        // Packed branch.
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(git_dir.join("packed-refs"), format!("# pack-refs\n{commit} refs/heads/main\n"))
+            .unwrap();
+        assert_eq!(super::read_head(&git_dir).as_deref(), Some(commit));
+
+        // Loose branch, which takes priority over packed.
+        let loose = "fedcba9876543210fedcba9876543210fedcba98";
+        fs::write(git_dir.join("refs/heads/main"), format!("{loose}\n")).unwrap();
+        assert_eq!(super::read_head(&git_dir).as_deref(), Some(loose));
+
+        fs::remove_dir_all(&git_dir).unwrap();
+    }
+}
@@ -0,0 +1,464 @@
+//! Minimal JSON serialization and parsing.
+//!
+//! This avoids depending on `serde` for the few machine-readable formats we
+//! output and read back.
+
+use std::{fmt, fmt::Write, iter::Peekable, str::CharIndices};
+
+/// Incrementally writes a JSON document, inserting commas as needed.
+#[derive(Default)]
+pub(crate) struct JsonWriter {
+    buf: String,
+
+    /// Whether each open object or array needs a comma before its next item.
+    needs_comma: Vec<bool>,
+
+    /// Whether a key was just written, so the next value belongs to it.
+    after_key: bool,
+}
+
+impl JsonWriter {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
+    /// Discards everything written so far, allowing the buffer to be reused.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.needs_comma.clear();
+        self.after_key = false;
+    }
+
+    /// Prepares for writing a value in the current object or array.
+    fn begin_value(&mut self) {
+        if self.after_key {
+            self.after_key = false;
+            return;
+        }
+
+        if let Some(needs_comma) = self.needs_comma.last_mut() {
+            if *needs_comma {
+                self.buf.push(',');
+            }
+            *needs_comma = true;
+        }
+    }
+
+    pub fn begin_object(&mut self) -> &mut Self {
+        self.begin_value();
+        self.buf.push('{');
+        self.needs_comma.push(false);
+        self
+    }
+
+    pub fn end_object(&mut self) -> &mut Self {
+        self.needs_comma.pop();
+        self.buf.push('}');
+        self
+    }
+
+    pub fn begin_array(&mut self) -> &mut Self {
+        self.begin_value();
+        self.buf.push('[');
+        self.needs_comma.push(false);
+        self
+    }
+
+    pub fn end_array(&mut self) -> &mut Self {
+        self.needs_comma.pop();
+        self.buf.push(']');
+        self
+    }
+
+    /// Writes an object key, after which a value must be written.
+    pub fn key(&mut self, key: &str) -> &mut Self {
+        self.begin_value();
+        write_escaped(&mut self.buf, key);
+        self.buf.push(':');
+        self.after_key = true;
+        self
+    }
+
+    pub fn str(&mut self, value: &str) -> &mut Self {
+        self.begin_value();
+        write_escaped(&mut self.buf, value);
+        self
+    }
+
+    pub fn bool(&mut self, value: bool) -> &mut Self {
+        self.begin_value();
+        self.buf.push_str(if value { "true" } else { "false" });
+        self
+    }
+
+    pub fn uint(&mut self, value: impl Into<u128>) -> &mut Self {
+        self.begin_value();
+        _ = write!(self.buf, "{}", value.into());
+        self
+    }
+
+    /// Writes a number, or `null` if not finite.
+    pub fn f64(&mut self, value: f64) -> &mut Self {
+        self.begin_value();
+        if value.is_finite() {
+            _ = write!(self.buf, "{value}");
+        } else {
+            self.buf.push_str("null");
+        }
+        self
+    }
+}
+
+fn write_escaped(buf: &mut String, s: &str) {
+    buf.push('"');
+
+    for ch in s.chars() {
+        match ch {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            ch if ch.is_control() => _ = write!(buf, "\\u{:04x}", ch as u32),
+            ch => buf.push(ch),
+        }
+    }
+
+    buf.push('"');
+}
+
+/// Parsed JSON value.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+
+    /// Key-value pairs in the order they were written.
+    Object(Vec<(String, JsonValue)>),
+}
+
+/// Error from parsing invalid JSON.
+#[derive(Debug)]
+pub(crate) struct JsonParseError {
+    /// The byte offset at which parsing failed.
+    offset: usize,
+}
+
+impl fmt::Display for JsonParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid JSON at byte {}", self.offset)
+    }
+}
+
+impl std::error::Error for JsonParseError {}
+
+impl JsonValue {
+    /// Parses a single JSON document.
+    pub fn parse(json: &str) -> Result<Self, JsonParseError> {
+        let mut parser = JsonParser { json, chars: json.char_indices().peekable() };
+
+        let value = parser.value()?;
+        parser.skip_whitespace();
+
+        match parser.chars.peek() {
+            None => Ok(value),
+            Some(_) => Err(parser.error()),
+        }
+    }
+
+    /// Returns the value of `key` if this is an object containing it.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_array(&self) -> Option<&[Self]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_object(&self) -> Option<&[(String, Self)]> {
+        match self {
+            Self::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    json: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl JsonParser<'_> {
+    fn error(&mut self) -> JsonParseError {
+        let offset = self.chars.peek().map_or(self.json.len(), |&(i, _)| i);
+        JsonParseError { offset }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, ch)| matches!(ch, ' ' | '\t' | '\n' | '\r')).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonParseError> {
+        match self.chars.next_if(|&(_, ch)| ch == expected) {
+            Some(_) => Ok(()),
+            None => Err(self.error()),
+        }
+    }
+
+    fn expect_str(&mut self, expected: &str) -> Result<(), JsonParseError> {
+        expected.chars().try_for_each(|ch| self.expect(ch))
+    }
+
+    fn value(&mut self) -> Result<JsonValue, JsonParseError> {
+        self.skip_whitespace();
+
+        let Some(&(_, ch)) = self.chars.peek() else {
+            return Err(self.error());
+        };
+
+        match ch {
+            'n' => self.expect_str("null").map(|_| JsonValue::Null),
+            't' => self.expect_str("true").map(|_| JsonValue::Bool(true)),
+            'f' => self.expect_str("false").map(|_| JsonValue::Bool(false)),
+            '"' => self.string().map(JsonValue::String),
+            '[' => self.array(),
+            '{' => self.object(),
+            '-' | '0'..='9' => self.number(),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn number(&mut self) -> Result<JsonValue, JsonParseError> {
+        let start = self.chars.peek().map_or(self.json.len(), |&(i, _)| i);
+
+        while self
+            .chars
+            .next_if(|(_, ch)| matches!(ch, '-' | '+' | '.' | 'e' | 'E' | '0'..='9'))
+            .is_some()
+        {}
+
+        let end = self.chars.peek().map_or(self.json.len(), |&(i, _)| i);
+
+        match self.json[start..end].parse() {
+            Ok(n) => Ok(JsonValue::Number(n)),
+            Err(_) => Err(JsonParseError { offset: start }),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonParseError> {
+        self.expect('"')?;
+
+        let mut result = String::new();
+
+        loop {
+            let Some((_, ch)) = self.chars.next() else {
+                return Err(self.error());
+            };
+
+            match ch {
+                '"' => return Ok(result),
+                '\\' => {
+                    let Some((_, escape)) = self.chars.next() else {
+                        return Err(self.error());
+                    };
+
+                    result.push(match escape {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.unicode_escape()?,
+                        _ => return Err(self.error()),
+                    });
+                }
+                ch => result.push(ch),
+            }
+        }
+    }
+
+    /// Parses the digits after `\u`, including a following low surrogate.
+    fn unicode_escape(&mut self) -> Result<char, JsonParseError> {
+        let high = self.hex4()?;
+
+        let code = if (0xD800..0xDC00).contains(&high) {
+            self.expect_str("\\u")?;
+            let low = self.hex4()?;
+            0x10000 + ((high - 0xD800) << 10) + low.wrapping_sub(0xDC00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error())
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonParseError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.chars.peek().and_then(|&(_, ch)| ch.to_digit(16));
+            let Some(digit) = digit else {
+                return Err(self.error());
+            };
+            self.chars.next();
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn array(&mut self) -> Result<JsonValue, JsonParseError> {
+        self.expect('[')?;
+
+        let mut values = Vec::new();
+
+        self.skip_whitespace();
+        if self.chars.next_if(|&(_, ch)| ch == ']').is_some() {
+            return Ok(JsonValue::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, ']')) => return Ok(JsonValue::Array(values)),
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue, JsonParseError> {
+        self.expect('{')?;
+
+        let mut entries = Vec::new();
+
+        self.skip_whitespace();
+        if self.chars.next_if(|&(_, ch)| ch == '}').is_some() {
+            return Ok(JsonValue::Object(entries));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+
+            self.skip_whitespace();
+            self.expect(':')?;
+
+            entries.push((key, self.value()?));
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, '}')) => return Ok(JsonValue::Object(entries)),
+                _ => return Err(self.error()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape() {
+        let mut json = JsonWriter::new();
+        json.str("a\"b\\c\nd\u{1}é");
+        assert_eq!(json.as_str(), r#""a\"b\\c\nd\u0001é""#);
+    }
+
+    #[test]
+    fn nesting() {
+        let mut json = JsonWriter::new();
+        json.begin_object();
+        json.key("a").uint(1u8);
+        json.key("b").begin_array().f64(0.5).f64(f64::NAN).bool(true).end_array();
+        json.key("c").begin_object().end_object();
+        json.end_object();
+        assert_eq!(json.as_str(), r#"{"a":1,"b":[0.5,null,true],"c":{}}"#);
+    }
+
+    #[test]
+    fn parse() {
+        let value = JsonValue::parse(
+            r#" {"a": [1, -2.5e3, null, true, false], "b": {"c": "d\"\u00e9\ud83d\ude00"}, "e": []} "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.get("a").and_then(JsonValue::as_array),
+            Some(
+                &[
+                    JsonValue::Number(1.0),
+                    JsonValue::Number(-2500.0),
+                    JsonValue::Null,
+                    JsonValue::Bool(true),
+                    JsonValue::Bool(false),
+                ][..]
+            )
+        );
+        assert_eq!(
+            value.get("b").and_then(|b| b.get("c")),
+            Some(&JsonValue::String("d\"é😀".into()))
+        );
+        assert_eq!(value.get("e").and_then(JsonValue::as_array), Some(&[][..]));
+        assert_eq!(value.get("f"), None);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for json in ["", "{", "[1,]", r#"{"a" 1}"#, "tru", "1 2", r#""\x""#] {
+            assert!(JsonValue::parse(json).is_err(), "{json:?}");
+        }
+    }
+
+    #[test]
+    fn parse_written() {
+        let mut json = JsonWriter::new();
+        json.begin_object();
+        json.key("a\n").str("\u{1}");
+        json.key("b").begin_array().f64(0.25).uint(7u8).end_array();
+        json.end_object();
+
+        let value = JsonValue::parse(json.as_str()).unwrap();
+        assert_eq!(value.get("a\n"), Some(&JsonValue::String("\u{1}".into())));
+        assert_eq!(value.get("b").and_then(|b| b.as_array()).map(<[_]>::len), Some(2));
+    }
+}
@@ -2,10 +2,13 @@ use std::{
     any::{Any, TypeId},
     num::NonZeroUsize,
     ops::{Deref, DerefMut},
+    path::PathBuf,
     sync::atomic::{AtomicUsize, Ordering::Relaxed},
 };
 
 pub mod fmt;
+pub mod git;
+pub mod json;
 pub mod sync;
 
 /// Public-in-private type like `()` but meant to be externally-unreachable.
@@ -101,6 +104,22 @@ pub(crate) fn known_parallelism() -> NonZeroUsize {
     }
 }
 
+/// Returns the directory in which files are written by default,
+/// `target/divan`.
+pub(crate) fn divan_dir() -> PathBuf {
+    let target_dir = std::env::var_os("CARGO_TARGET_DIR").map(PathBuf::from).or_else(|| {
+        // Benchmarks are built as `target/<profile>/deps/<name>-<hash>`.
+        let exe = std::env::current_exe().ok()?;
+        let deps_dir = exe.parent()?;
+        if deps_dir.file_name()? != "deps" {
+            return None;
+        }
+        Some(deps_dir.parent()?.parent()?.to_path_buf())
+    });
+
+    target_dir.unwrap_or_else(|| PathBuf::from("target")).join("divan")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::black_box;
@@ -10,6 +10,9 @@ use divan::Divan;
 static CHILD1_ITERS: AtomicUsize = AtomicUsize::new(0);
 static CHILD2_ITERS: AtomicUsize = AtomicUsize::new(0);
 static CHILD3_ITERS: AtomicUsize = AtomicUsize::new(0);
+static CHILD4_ITERS: AtomicUsize = AtomicUsize::new(0);
+static CHILD5_ITERS: AtomicUsize = AtomicUsize::new(0);
+static CHILD6_ITERS: AtomicUsize = AtomicUsize::new(0);
 
 #[divan::bench_group(sample_count = 10, sample_size = 50)]
 mod parent {
@@ -46,6 +49,45 @@ mod parent {
             CHILD3_ITERS.fetch_add(1, SeqCst);
         }
     }
+
+    // (1 + 2 + … + 10) × 50 = 2750
+    #[divan::bench_group(sampling = linear)]
+    mod child4 {
+        use super::*;
+
+        #[divan::bench]
+        fn bench() {
+            CHILD4_ITERS.fetch_add(1, SeqCst);
+        }
+    }
+
+    // 100 + 10 × 50 = 600
+    #[divan::bench_group(warmup_iters = 100)]
+    mod child5 {
+        use super::*;
+
+        #[divan::bench]
+        fn bench() {
+            CHILD5_ITERS.fetch_add(1, SeqCst);
+        }
+    }
+
+    // Never precise enough, so the sample count doubles from 10 until capped
+    // at 64 times: 640 × 1 = 640
+    #[divan::bench_group(sample_size = 1, target_precision = 1e-9)]
+    mod child6 {
+        use super::*;
+
+        #[divan::bench]
+        fn bench() {
+            // Alternate between fast and slow iterations to be noisy.
+            if CHILD6_ITERS.fetch_add(1, SeqCst) % 2 == 1 {
+                for _ in 0..10_000 {
+                    std::hint::spin_loop();
+                }
+            }
+        }
+    }
 }
 
 #[test]
@@ -55,4 +97,7 @@ fn iter_count() {
     assert_eq!(CHILD1_ITERS.load(SeqCst), 10);
     assert_eq!(CHILD2_ITERS.load(SeqCst), 2100);
     assert_eq!(CHILD3_ITERS.load(SeqCst), 50);
+    assert_eq!(CHILD4_ITERS.load(SeqCst), 2750);
+    assert_eq!(CHILD5_ITERS.load(SeqCst), 600);
+    assert_eq!(CHILD6_ITERS.load(SeqCst), 640);
 }
@@ -32,6 +32,18 @@ mod ignored_group {
     fn not_yet_ignored() {}
 }
 
+#[divan::bench(baseline = outer)]
+fn compared() {}
+
+#[divan::bench_group]
+mod compared_group {
+    #[divan::bench(baseline = super::outer)]
+    fn compared_inner() {}
+}
+
+#[divan::bench(types = [i32, u8], baseline_type = i32)]
+fn compared_types<T>() {}
+
 /// Finds `EntryMeta` based on the entry's raw name.
 macro_rules! find_meta {
     ($entries:expr, $raw_name:literal) => {
@@ -118,3 +130,25 @@ fn ignore() {
     assert!(!get_ignore(find_outer()));
     assert!(!get_ignore(find_outer_group()));
 }
+
+#[test]
+fn baseline() {
+    let outer = find_outer();
+
+    let compared = find_meta!(BENCH_ENTRIES, "compared");
+    assert!(compared.baseline.is_some_and(|baseline| std::ptr::eq(baseline, outer)));
+
+    let compared_inner = find_meta!(BENCH_ENTRIES, "compared_inner");
+    assert!(compared_inner.baseline.is_some_and(|baseline| std::ptr::eq(baseline, outer)));
+
+    assert!(outer.baseline.is_none());
+    assert!(find_outer_group().baseline.is_none());
+}
+
+#[test]
+fn baseline_type() {
+    assert!(find_meta!(GROUP_ENTRIES, "compared_types").baseline_type.is_some());
+
+    assert!(find_outer().baseline_type.is_none());
+    assert!(find_outer_group().baseline_type.is_none());
+}
@@ -0,0 +1,27 @@
+// Tests that linear sampling runs about as many iterations as flat sampling.
+
+// Miri cannot discover benchmarks.
+#![cfg(not(miri))]
+
+use std::{thread, time::Duration};
+
+use divan::Divan;
+
+// Tuning stops at 1 iteration per sample, which would make 100 linear samples
+// run 1 + 2 + … + 100 = 5050 iterations.
+#[divan::bench(sampling = linear, sample_count = 100)]
+fn slow() {
+    thread::sleep(Duration::from_micros(200));
+}
+
+#[test]
+fn iter_count() {
+    // Internal benchmarks are registered when testing the whole workspace.
+    let report = Divan::default().skip_regex("^divan::").run_benches_collect();
+
+    // Instead, 1 + 2 + … + 13 = 91 iterations fit within the 100 of flat
+    // sampling.
+    let stats = report.get("linear_sampling::slow").unwrap().stats().unwrap();
+    assert_eq!(stats.sample_count(), 13);
+    assert_eq!(stats.iter_count(), 91);
+}
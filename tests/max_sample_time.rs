@@ -0,0 +1,53 @@
+// Tests that `max_sample_time` limits automatically chosen sample sizes.
+
+// Miri cannot discover benchmarks.
+#![cfg(not(miri))]
+
+use std::{
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+use divan::{Bencher, Divan};
+
+static SLOW_LATER_START: OnceLock<Instant> = OnceLock::new();
+
+// Generating inputs takes longer than the budget, so samples never grow.
+#[divan::bench(sample_count = 10, max_sample_time = Duration::from_micros(50))]
+fn slow_inputs(bencher: Bencher) {
+    bencher.with_inputs(|| thread::sleep(Duration::from_micros(100))).bench_values(|()| {});
+}
+
+// Fast while tuning but slow after 1ms, so collection restarts with smaller
+// samples until they fit the budget.
+#[divan::bench(sample_count = 1000, max_sample_time = Duration::from_millis(1))]
+fn slow_later() {
+    let start = *SLOW_LATER_START.get_or_init(Instant::now);
+    if start.elapsed() >= Duration::from_millis(1) {
+        thread::sleep(Duration::from_micros(100));
+    }
+}
+
+#[test]
+fn sample_size() {
+    // Internal benchmarks are registered when testing the whole workspace.
+    let report = Divan::default().skip_regex("^divan::").run_benches_collect();
+
+    let stats = |name: &str| report.get(&format!("max_sample_time::{name}"))?.stats();
+
+    // Every collected sample has a single iteration.
+    let slow_inputs = stats("slow_inputs").unwrap();
+    assert_eq!(slow_inputs.sample_count(), 10);
+    assert_eq!(slow_inputs.iter_count(), 10);
+
+    // Samples from before the slowdown are discarded rather than mixed with
+    // smaller slow samples, and at most 10 slow iterations fit 1ms.
+    //
+    // Fast samples may remain if their size already fit the budget when the
+    // slowdown happened, so the fastest time is not checked.
+    let slow_later = stats("slow_later").unwrap();
+    assert_eq!(slow_later.sample_count(), 1000);
+    assert_eq!(slow_later.iter_count() % 1000, 0);
+    assert!(slow_later.iter_count() <= 10 * 1000);
+}
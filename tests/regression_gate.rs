@@ -0,0 +1,74 @@
+// Tests that `--fail-on-regression` exits with an error status.
+
+// Miri cannot discover benchmarks.
+#![cfg(not(miri))]
+
+use std::{
+    process::{Command, ExitStatus, Stdio},
+    time::Duration,
+};
+
+use divan::Divan;
+
+/// Set in child processes to the action they perform.
+const CHILD_ACTION: &str = "DIVAN_TEST_GATE_ACTION";
+
+/// Set in child processes to make `bench` slower.
+const CHILD_SLOW: &str = "DIVAN_TEST_GATE_SLOW";
+
+const BASELINE: &str = "regression_gate_test";
+
+#[divan::bench(sample_count = 10, sample_size = 1)]
+fn bench() {
+    if std::env::var_os(CHILD_SLOW).is_some() {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Runs benchmarks in a child process, since the gate exits the process.
+#[test]
+fn child() {
+    let Ok(action) = std::env::var(CHILD_ACTION) else {
+        return;
+    };
+
+    // Internal benchmarks are registered when testing the whole workspace.
+    let divan = Divan::default().skip_regex("^divan::");
+    match action.as_str() {
+        "save" => divan.save_baseline(BASELINE),
+        "compare" => divan.baseline(BASELINE).fail_on_regression(0.1),
+        "missing" => divan.baseline("regression_gate_test_missing").fail_on_regression(0.1),
+        _ => unreachable!(),
+    }
+    .run_benches();
+}
+
+fn run_child(action: &str, slow: bool) -> ExitStatus {
+    let mut command = Command::new(std::env::current_exe().unwrap());
+    command
+        .args(["--exact", "child"])
+        .env(CHILD_ACTION, action)
+        .env_remove(CHILD_SLOW)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    if slow {
+        command.env(CHILD_SLOW, "1");
+    }
+
+    command.status().unwrap()
+}
+
+#[test]
+fn exit_status() {
+    // Becoming slower fails.
+    assert!(run_child("save", false).success());
+    assert_eq!(run_child("compare", true).code(), Some(1));
+
+    // Becoming faster passes.
+    assert!(run_child("save", true).success());
+    assert!(run_child("compare", false).success());
+
+    // A missing baseline fails rather than passing without comparison.
+    assert_eq!(run_child("missing", false).code(), Some(1));
+}